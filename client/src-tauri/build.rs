@@ -1,3 +1,69 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn git(args: &[&str]) -> Option<String> {
+  let output = Command::new("git").args(args).output().ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+  (!value.is_empty()).then_some(value)
+}
+
+/// Seconds since the epoch as an RFC 3339 UTC timestamp.
+fn rfc3339(secs: u64) -> String {
+  let days = (secs / 86_400) as i64;
+  let rem = secs % 86_400;
+  // Howard Hinnant's civil_from_days
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  format!(
+    "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+    rem / 3_600,
+    rem % 3_600 / 60,
+    rem % 60
+  )
+}
+
+/// Build metadata for `get_app_info`. SOURCE_DATE_EPOCH keeps the timestamp
+/// reproducible; outside a git checkout the commit is "unknown".
+fn emit_build_info() {
+  let commit = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".into());
+  let secs = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or_else(|| {
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+    });
+  println!("cargo:rustc-env=NEXUS_COMMIT_HASH={commit}");
+  println!("cargo:rustc-env=NEXUS_BUILD_TIMESTAMP={}", rfc3339(secs));
+  println!(
+    "cargo:rustc-env=NEXUS_TARGET_TRIPLE={}",
+    std::env::var("TARGET").unwrap_or_default()
+  );
+  println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+  // Rebuild when HEAD moves
+  let head_ref = git(&["rev-parse", "--symbolic-full-name", "HEAD"])
+    .and_then(|r| git(&["rev-parse", "--git-path", &r]));
+  for path in [git(&["rev-parse", "--git-path", "HEAD"]), head_ref]
+    .into_iter()
+    .flatten()
+  {
+    println!("cargo:rerun-if-changed={path}");
+  }
+}
+
 fn main() {
+  emit_build_info();
   tauri_build::build()
 }
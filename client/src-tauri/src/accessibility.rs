@@ -0,0 +1,158 @@
+// OS accessibility preferences the frontend gates animations and effects on.
+//
+// - macOS: NSWorkspace's accessibilityDisplayShouldReduceMotion,
+//   ...ShouldReduceTransparency and ...ShouldIncreaseContrast.
+// - Windows: SystemParametersInfo (client area animation, high contrast)
+//   and the Personalize "EnableTransparency" registry value.
+// - Linux: GNOME's gsettings (enable-animations, high-contrast). There's no
+//   transparency setting, so that one is always false.
+//
+// Anything that can't be read counts as false. A background thread polls
+// and emits `accessibility-prefs-changed` with the new prefs on any change.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AccessibilityPrefs {
+    pub reduce_motion: bool,
+    pub reduce_transparency: bool,
+    pub high_contrast: bool,
+}
+
+#[cfg(target_os = "macos")]
+fn read() -> AccessibilityPrefs {
+    use objc2::msg_send;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+
+    let Some(class) = AnyClass::get(c"NSWorkspace") else {
+        return AccessibilityPrefs::default();
+    };
+    // SAFETY: plain property reads on the shared NSWorkspace
+    unsafe {
+        let workspace: *mut AnyObject = msg_send![class, sharedWorkspace];
+        let Some(workspace) = workspace.as_ref() else {
+            return AccessibilityPrefs::default();
+        };
+        let reduce_motion: Bool = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+        let reduce_transparency: Bool =
+            msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+        let high_contrast: Bool = msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+        AccessibilityPrefs {
+            reduce_motion: reduce_motion.as_bool(),
+            reduce_transparency: reduce_transparency.as_bool(),
+            high_contrast: high_contrast.as_bool(),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read() -> AccessibilityPrefs {
+    use std::ffi::c_void;
+
+    use windows::core::{w, BOOL};
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+    use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    unsafe {
+        let mut animations = BOOL(1);
+        let _ = SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut animations as *mut BOOL as *mut c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        );
+
+        let mut contrast = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        let high_contrast = SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            Some(&mut contrast as *mut HIGHCONTRASTW as *mut c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .is_ok()
+            && (contrast.dwFlags & HCF_HIGHCONTRASTON).0 != 0;
+
+        let mut transparency: u32 = 1;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let _ = RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize"),
+            w!("EnableTransparency"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut transparency as *mut u32 as *mut c_void),
+            Some(&mut size),
+        );
+
+        AccessibilityPrefs {
+            reduce_motion: !animations.as_bool(),
+            reduce_transparency: transparency == 0,
+            high_contrast,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read() -> AccessibilityPrefs {
+    fn gsettings_bool(schema: &str, key: &str) -> Option<bool> {
+        let output = std::process::Command::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()?;
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    AccessibilityPrefs {
+        reduce_motion: gsettings_bool("org.gnome.desktop.interface", "enable-animations")
+            .is_some_and(|enabled| !enabled),
+        reduce_transparency: false,
+        high_contrast: gsettings_bool("org.gnome.desktop.a11y.interface", "high-contrast")
+            .unwrap_or(false),
+    }
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    let mut last = read();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = read();
+        if current != last {
+            last = current;
+            let _ = app.emit("accessibility-prefs-changed", current);
+        }
+    }
+}
+
+/// Start watching for preference changes. Started by `deferred_init`.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("accessibility-monitor".into())
+        .spawn(move || poll_loop(handle))
+    {
+        log::warn!("failed to start accessibility monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn get_accessibility_prefs() -> Result<AccessibilityPrefs, String> {
+    tauri::async_runtime::spawn_blocking(read)
+        .await
+        .map_err(|e| e.to_string())
+}
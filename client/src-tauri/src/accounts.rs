@@ -0,0 +1,257 @@
+// Additional account windows. Each one gets its own label
+// (`account-<id>`) and its own webview data directory, so cookies and
+// localStorage don't leak between accounts. The main window is the default
+// account and takes part in the same bookkeeping: "Open" from the tray or a
+// second launch goes to whichever account window was focused last, and
+// closing the last visible one goes through close-to-tray instead of
+// quitting.
+//
+// An account can have its own proxy, saved in `account_proxies` under the
+// same account id that names its window and data directory. It replaces
+// the global proxy for that window only, and changing it leaves every other
+// window alone.
+
+use std::collections::HashMap;
+#[cfg(not(target_os = "macos"))]
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow};
+
+use crate::close_to_tray;
+use crate::proxy::{ProxyApplied, ProxyConfig};
+use crate::settings::SettingsStore;
+
+const LABEL_PREFIX: &str = "account-";
+
+#[derive(Default)]
+struct Registry {
+    /// Open account windows by label (excluding main), with their account id
+    windows: HashMap<String, String>,
+    last_focused: Option<String>,
+}
+
+#[derive(Default)]
+pub struct AccountWindows(Mutex<Registry>);
+
+/// Account ids name the window label, the data directory and the macOS data
+/// store, so they're limited to what all three take unchanged: ASCII
+/// letters, digits, `-` and `_`. Mapping other characters instead would let
+/// two accounts share a session.
+fn check_account_id(account_id: &str) -> Result<(), String> {
+    let valid = !account_id.is_empty()
+        && account_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid account id \"{account_id}\": only letters, digits, - and _ are allowed"
+        ))
+    }
+}
+
+fn window_label(account_id: &str) -> String {
+    format!("{LABEL_PREFIX}{account_id}")
+}
+
+/// The main window or a per-account one.
+pub(crate) fn is_account_window(label: &str) -> bool {
+    label == "main" || label.starts_with(LABEL_PREFIX)
+}
+
+fn account_id_for<R: Runtime>(app: &AppHandle<R>, label: &str) -> Option<String> {
+    app.try_state::<AccountWindows>()
+        .and_then(|s| s.0.lock().unwrap().windows.get(label).cloned())
+}
+
+fn saved_proxy<R: Runtime>(app: &AppHandle<R>, account_id: &str) -> Option<ProxyConfig> {
+    app.state::<SettingsStore>()
+        .get()
+        .account_proxies
+        .remove(account_id)
+}
+
+/// Whether the window's account has its own proxy, so the global one
+/// shouldn't be applied to it.
+pub(crate) fn has_proxy_override<R: Runtime>(app: &AppHandle<R>, label: &str) -> bool {
+    account_id_for(app, label).is_some_and(|id| saved_proxy(app, &id).is_some())
+}
+
+/// The account window "Open" should bring back: the most recently focused
+/// one that's still open, falling back to the main window.
+pub fn last_focused<R: Runtime>(app: &AppHandle<R>) -> Option<WebviewWindow<R>> {
+    let label = app
+        .try_state::<AccountWindows>()
+        .and_then(|s| s.0.lock().unwrap().last_focused.clone());
+    label
+        .and_then(|l| app.get_webview_window(&l))
+        .or_else(|| app.get_webview_window("main"))
+}
+
+/// Track focus and route close requests for an account window (including
+/// main).
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    let w = window.clone();
+    window.on_window_event(move |event| {
+        let app = w.app_handle();
+        match event {
+            tauri::WindowEvent::Focused(true) => {
+                if let Some(state) = app.try_state::<AccountWindows>() {
+                    state.0.lock().unwrap().last_focused = Some(w.label().to_string());
+                }
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                let others_visible = app.webview_windows().values().any(|other| {
+                    other.label() != w.label()
+                        && is_account_window(other.label())
+                        && other.is_visible().unwrap_or(false)
+                });
+                if others_visible {
+                    // The main window is never destroyed; other account
+                    // windows close for real
+                    if w.label() == "main" {
+                        api.prevent_close();
+                        let _ = w.hide();
+                        crate::window_events::refresh(app);
+                    }
+                } else if close_to_tray::is_enabled(app) {
+                    api.prevent_close();
+                    close_to_tray::hide_to_tray(&w);
+                } else {
+                    app.exit(0);
+                }
+            }
+            tauri::WindowEvent::Destroyed => {
+                if let Some(state) = app.try_state::<AccountWindows>() {
+                    let mut registry = state.0.lock().unwrap();
+                    registry.windows.remove(w.label());
+                    if registry.last_focused.as_deref() == Some(w.label()) {
+                        registry.last_focused = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// A stable 16-byte identifier for WKWebView's data store, derived from the
+/// account id (two FNV-1a passes with different offsets).
+#[cfg(target_os = "macos")]
+pub(crate) fn data_store_identifier(account_id: &str) -> [u8; 16] {
+    let fnv = |offset: u64| {
+        account_id.bytes().fold(offset, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+    };
+    let mut id = [0u8; 16];
+    id[..8].copy_from_slice(&fnv(0xcbf29ce484222325).to_le_bytes());
+    id[8..].copy_from_slice(&fnv(0x84222325cbf29ce4).to_le_bytes());
+    id
+}
+
+/// The account's own webview data directory.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn data_directory(app: &AppHandle, account_id: &str) -> tauri::Result<PathBuf> {
+    Ok(crate::paths::data_dir(app)?.join("accounts").join(account_id))
+}
+
+/// Save (or with `None`, clear) an account's proxy override.
+fn save_proxy(app: &AppHandle, account_id: &str, proxy: Option<ProxyConfig>) -> Result<(), String> {
+    check_account_id(account_id)?;
+    if let Some(proxy) = &proxy {
+        proxy.validate()?;
+    }
+    app.state::<SettingsStore>().update(app, |s| match proxy {
+        Some(proxy) => {
+            s.account_proxies.insert(account_id.to_string(), proxy);
+        }
+        None => {
+            s.account_proxies.remove(account_id);
+        }
+    });
+    Ok(())
+}
+
+/// Open (or focus) the window for `account_id`, with its own isolated
+/// webview session. A `proxy` is saved as the account's override.
+#[tauri::command]
+pub fn open_account_window(
+    app: AppHandle,
+    account_id: String,
+    proxy: Option<ProxyConfig>,
+) -> Result<(), String> {
+    check_account_id(&account_id)?;
+    if proxy.is_some() {
+        save_proxy(&app, &account_id, proxy)?;
+    }
+    let proxy = saved_proxy(&app, &account_id).unwrap_or_else(|| crate::proxy::current(&app));
+    let label = window_label(&account_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        crate::window_events::refresh(&app);
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let window = crate::build_app_window(
+        &app,
+        &label,
+        WebviewUrl::App("index.html".into()),
+        crate::AppWindowOptions {
+            account_id: Some(&account_id),
+            proxy: &proxy,
+            always_on_top: false,
+            transparent: false,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    app.state::<AccountWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .windows
+        .insert(label, account_id);
+    crate::menu_visibility::apply(&window);
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Account ids of the open account windows (the main window excluded).
+#[tauri::command]
+pub fn list_account_windows(app: AppHandle) -> Vec<String> {
+    app.state::<AccountWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .windows
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// Change (or with `None`, remove) an account's proxy override. An open
+/// window for the account switches over where the platform allows it.
+#[tauri::command]
+pub fn set_account_proxy(
+    app: AppHandle,
+    account_id: String,
+    proxy: Option<ProxyConfig>,
+) -> Result<ProxyApplied, String> {
+    save_proxy(&app, &account_id, proxy)?;
+    let label = window_label(&account_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let proxy = saved_proxy(&app, &account_id).unwrap_or_else(|| crate::proxy::current(&app));
+        crate::proxy::apply_to_webview(&window, &proxy);
+    }
+    Ok(ProxyApplied {
+        requires_restart: cfg!(target_os = "windows"),
+    })
+}
+
+#[tauri::command]
+pub fn get_account_proxy(app: AppHandle, account_id: String) -> Option<ProxyConfig> {
+    saved_proxy(&app, &account_id)
+}
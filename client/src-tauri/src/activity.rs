@@ -0,0 +1,100 @@
+// Rich presence ("now playing"). The frontend reports what the user is doing
+// and we publish it to the OS where there's somewhere to publish it. No
+// platform backend exists yet, so publishing is a no-op apart from logging;
+// the state handling is in place so one can be added in `publish`.
+//
+// Updates are debounced: switching channels quickly only publishes the
+// final activity. The latest activity is kept so `reapply_activity` can
+// re-publish it once a backend connection comes back.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const PUBLISH_DEBOUNCE: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityPayload {
+    pub state: String,
+    pub details: Option<String>,
+    /// Unix time in milliseconds the activity started, for an elapsed timer
+    pub start_timestamp: Option<u64>,
+}
+
+#[derive(Default)]
+struct Inner {
+    current: Option<ActivityPayload>,
+    /// What was last handed to the backend
+    published: Option<ActivityPayload>,
+    /// Time of the most recent update, and whether a publish is scheduled
+    pending: Option<Instant>,
+    scheduled: bool,
+}
+
+#[derive(Default, Clone)]
+pub struct ActivityState(Arc<Mutex<Inner>>);
+
+fn publish(activity: Option<&ActivityPayload>) {
+    match activity {
+        Some(a) => log::debug!("activity: {} {:?}", a.state, a.details),
+        None => log::debug!("activity cleared"),
+    }
+}
+
+impl ActivityState {
+    fn set(&self, activity: Option<ActivityPayload>) {
+        let mut inner = self.0.lock().unwrap();
+        inner.current = activity;
+        inner.pending = Some(Instant::now());
+        if inner.scheduled {
+            return;
+        }
+        inner.scheduled = true;
+        drop(inner);
+
+        let state = self.0.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(PUBLISH_DEBOUNCE);
+            let mut inner = state.lock().unwrap();
+            if inner.pending.is_some_and(|t| t.elapsed() < PUBLISH_DEBOUNCE) {
+                continue;
+            }
+            inner.scheduled = false;
+            if inner.published != inner.current {
+                inner.published = inner.current.clone();
+                publish(inner.published.as_ref());
+            }
+            break;
+        });
+    }
+
+    fn reapply(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.published = inner.current.clone();
+        publish(inner.published.as_ref());
+    }
+}
+
+#[tauri::command]
+pub fn set_activity(app: AppHandle, activity: ActivityPayload) {
+    app.state::<ActivityState>().set(Some(activity));
+}
+
+#[tauri::command]
+pub fn clear_activity(app: AppHandle) {
+    app.state::<ActivityState>().set(None);
+}
+
+/// Publish the current activity again, e.g. after the connection it's
+/// published over was re-established.
+#[tauri::command]
+pub fn reapply_activity(app: AppHandle) {
+    app.state::<ActivityState>().reapply();
+}
+
+#[tauri::command]
+pub fn get_activity(app: AppHandle) -> Option<ActivityPayload> {
+    app.state::<ActivityState>().0.lock().unwrap().current.clone()
+}
@@ -0,0 +1,31 @@
+// "Always on Top" — pin the main window above others, e.g. during screen shares.
+
+use tauri::menu::CheckMenuItem;
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::settings::SettingsStore;
+
+/// Handle to the View menu's "Always on Top" check item so it can be kept in
+/// sync when the frontend changes the value.
+pub struct AlwaysOnTopMenuItem(pub CheckMenuItem<Wry>);
+
+/// Apply and persist the setting. Deliberately doesn't focus or show the
+/// window, so toggling it from a shortcut won't pull focus away from a
+/// full-screen app that's being shared.
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| e.to_string())?;
+    app.state::<SettingsStore>()
+        .update(app, |s| s.always_on_top = enabled);
+    if let Some(item) = app.try_state::<AlwaysOnTopMenuItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
+    set_enabled(&app, enabled)
+}
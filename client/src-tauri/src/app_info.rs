@@ -0,0 +1,27 @@
+// Build details for the settings "About" page and bug reports. The commit,
+// build time and target come from env vars set by `build.rs`.
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    pub version: String,
+    pub tauri_version: &'static str,
+    /// Short hash, or "unknown" when built outside a git checkout
+    pub commit_hash: &'static str,
+    /// RFC 3339, UTC
+    pub build_timestamp: &'static str,
+    pub target_triple: &'static str,
+}
+
+#[tauri::command]
+pub fn get_app_info(app: AppHandle) -> AppInfo {
+    AppInfo {
+        version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION,
+        commit_hash: env!("NEXUS_COMMIT_HASH"),
+        build_timestamp: env!("NEXUS_BUILD_TIMESTAMP"),
+        target_triple: env!("NEXUS_TARGET_TRIPLE"),
+    }
+}
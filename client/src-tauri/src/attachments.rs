@@ -0,0 +1,93 @@
+// "Save Attachment As…": a native save dialog instead of the webview's
+// download handling, whose target folder varies by platform.
+
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tokio::io::AsyncWriteExt;
+
+/// Only the file name part of what the sender supplied; never a path.
+fn safe_file_name(suggested: &str) -> String {
+    let name = suggested
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".into()
+    } else {
+        name.into()
+    }
+}
+
+/// Ask where to save. `None` if the dialog was cancelled.
+fn prompt(app: &AppHandle, suggested_name: &str) -> Result<Option<PathBuf>, String> {
+    let name = safe_file_name(suggested_name);
+    let mut dialog = app.dialog().file().set_file_name(&name);
+    if let Some(ext) = Path::new(&name).extension().and_then(|e| e.to_str()) {
+        dialog = dialog.add_filter(ext.to_uppercase(), &[ext]);
+    }
+    match dialog.blocking_save_file() {
+        Some(path) => path.into_path().map(Some).map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// Save `bytes` wherever the user picks. Returns the saved path, or `None`
+/// if the dialog was cancelled.
+#[tauri::command]
+pub async fn save_attachment(
+    app: AppHandle,
+    bytes: Vec<u8>,
+    suggested_name: String,
+) -> Result<Option<PathBuf>, String> {
+    let Some(dest) = prompt(&app, &suggested_name)? else {
+        return Ok(None);
+    };
+    tokio::fs::write(&dest, bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Some(dest))
+}
+
+/// Download `url` (through the configured proxy) and stream it to wherever
+/// the user picks, so large files are never held in memory. The file only
+/// appears under its final name once the download completes.
+#[tauri::command]
+pub async fn save_attachment_from_url(
+    app: AppHandle,
+    url: String,
+    suggested_name: String,
+) -> Result<Option<PathBuf>, String> {
+    let Some(dest) = prompt(&app, &suggested_name)? else {
+        return Ok(None);
+    };
+    let mut response = crate::http::client(&app)?
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    let mut part = dest.clone().into_os_string();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let result: Result<(), String> = async {
+        let mut file = tokio::fs::File::create(&part)
+            .await
+            .map_err(|e| e.to_string())?;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&part, &dest)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&part).await;
+    }
+    result.map(|()| Some(dest))
+}
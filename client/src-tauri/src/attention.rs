@@ -0,0 +1,32 @@
+// Taskbar flash / dock bounce when the user is mentioned while Nexus is in
+// the background.
+
+use tauri::{Runtime, UserAttentionType, WebviewWindow};
+
+/// Flash the taskbar entry (Windows/Linux) or bounce the dock icon (macOS).
+/// `critical` keeps it going until the window is focused; otherwise it's a
+/// single flash/bounce.
+#[tauri::command]
+pub fn request_user_attention(window: WebviewWindow, critical: bool) -> Result<(), String> {
+    if window.is_focused().unwrap_or(false) {
+        return Ok(());
+    }
+    let kind = if critical {
+        UserAttentionType::Critical
+    } else {
+        UserAttentionType::Informational
+    };
+    window
+        .request_user_attention(Some(kind))
+        .map_err(|e| e.to_string())
+}
+
+/// Clear any pending attention request once the window regains focus.
+pub fn clear_on_focus<R: Runtime>(window: &WebviewWindow<R>) {
+    let w = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(true) = event {
+            let _ = w.request_user_attention(None);
+        }
+    });
+}
@@ -0,0 +1,164 @@
+// Audio device enumeration for the voice settings screen.
+//
+// Devices are listed across every audio host cpal knows about on this
+// platform (e.g. ALSA and JACK on Linux, WASAPI and ASIO on Windows), so each
+// entry carries its host name. Device ids are `<host>:<device name>` since
+// cpal has no stable device identifier of its own.
+//
+// cpal has no hotplug notifications either, so a background thread
+// re-enumerates periodically and emits `audio-devices-changed` with the full
+// lists whenever a device comes or goes or the OS default changes.
+
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::settings::SettingsStore;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AudioDevices {
+    pub inputs: Vec<AudioDevice>,
+    pub outputs: Vec<AudioDevice>,
+}
+
+fn device_id(host: &cpal::Host, name: &str) -> String {
+    format!("{}:{}", host.id().name(), name)
+}
+
+/// Resolve a device id from `list_audio_devices`; `None` means the default
+/// host's default device. Returns `None` if the device is gone.
+pub(crate) fn find_device(id: Option<&str>, input: bool) -> Option<cpal::Device> {
+    let Some(id) = id else {
+        let host = cpal::default_host();
+        return if input {
+            host.default_input_device()
+        } else {
+            host.default_output_device()
+        };
+    };
+    let (host_name, name) = id.split_once(':')?;
+    cpal::available_hosts()
+        .into_iter()
+        .find(|h| h.name() == host_name)
+        .and_then(|h| cpal::host_from_id(h).ok())
+        .and_then(|host| {
+            let devices = if input {
+                host.input_devices().ok()?.collect::<Vec<_>>()
+            } else {
+                host.output_devices().ok()?.collect()
+            };
+            devices.into_iter().find(|d| d.name().ok().as_deref() == Some(name))
+        })
+}
+
+fn collect<I>(host: &cpal::Host, devices: I, default_name: Option<String>) -> Vec<AudioDevice>
+where
+    I: Iterator<Item = cpal::Device>,
+{
+    devices
+        .filter_map(|d| d.name().ok())
+        .map(|name| AudioDevice {
+            id: device_id(host, &name),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            host: host.id().name().to_string(),
+            name,
+        })
+        .collect()
+}
+
+fn enumerate() -> AudioDevices {
+    let mut result = AudioDevices::default();
+    // Only the platform's default host decides the default devices
+    let default_host = cpal::default_host().id();
+    for host_id in cpal::available_hosts() {
+        let Ok(host) = cpal::host_from_id(host_id) else {
+            continue;
+        };
+        let is_default_host = host_id == default_host;
+        let default_input = is_default_host
+            .then(|| host.default_input_device().and_then(|d| d.name().ok()))
+            .flatten();
+        let default_output = is_default_host
+            .then(|| host.default_output_device().and_then(|d| d.name().ok()))
+            .flatten();
+        if let Ok(inputs) = host.input_devices() {
+            result.inputs.extend(collect(&host, inputs, default_input));
+        }
+        if let Ok(outputs) = host.output_devices() {
+            result.outputs.extend(collect(&host, outputs, default_output));
+        }
+    }
+    result
+}
+
+/// List the OS audio input and output devices. Returns empty lists rather
+/// than an error when no devices (or no audio host) are available.
+#[tauri::command]
+pub async fn list_audio_devices() -> Result<AudioDevices, String> {
+    // Probing ALSA devices can take a noticeable amount of time
+    tauri::async_runtime::spawn_blocking(enumerate)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    let mut last = enumerate();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = enumerate();
+        if current != last {
+            log::info!(
+                "audio devices changed: {} inputs, {} outputs",
+                current.inputs.len(),
+                current.outputs.len()
+            );
+            let _ = app.emit("audio-devices-changed", &current);
+            last = current;
+        }
+    }
+}
+
+/// Start watching for device changes. Started by `deferred_init`.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("audio-device-monitor".into())
+        .spawn(move || poll_loop(handle))
+    {
+        log::warn!("failed to start audio device monitor: {e}");
+    }
+}
+
+/// The output picked with `set_output_device`, if any.
+pub(crate) fn selected_output<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.state::<SettingsStore>().get().output_device
+}
+
+/// Make `device_id` (from `list_audio_devices`) the output for app audio.
+/// Fails if the device isn't connected any more.
+#[tauri::command]
+pub async fn set_output_device(app: AppHandle, device_id: String) -> Result<AudioDevice, String> {
+    let devices = tauri::async_runtime::spawn_blocking(enumerate)
+        .await
+        .map_err(|e| e.to_string())?;
+    let device = devices
+        .outputs
+        .into_iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("output device \"{device_id}\" is no longer available"))?;
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.output_device = Some(device_id));
+    Ok(device)
+}
@@ -0,0 +1,45 @@
+// Launch at login, via tauri-plugin-autostart. The OS registration is the
+// source of truth, so every toggle reports back the state the plugin reads,
+// not the state we asked for.
+
+use tauri::menu::CheckMenuItem;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_autostart::ManagerExt;
+
+/// Handle to the "Start Nexus at Login" check item.
+pub struct AutostartMenuItem(pub CheckMenuItem<Wry>);
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+/// Register or unregister the login item and return the resulting state.
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<bool, String> {
+    let launcher = app.autolaunch();
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+    let actual = is_enabled(app);
+    if let Some(item) = app.try_state::<AutostartMenuItem>() {
+        let _ = item.0.set_checked(actual);
+    }
+    result.map_err(|e| e.to_string())?;
+    Ok(actual)
+}
+
+#[tauri::command]
+pub fn enable_autostart(app: AppHandle) -> Result<bool, String> {
+    set_enabled(&app, true)
+}
+
+#[tauri::command]
+pub fn disable_autostart(app: AppHandle) -> Result<bool, String> {
+    set_enabled(&app, false)
+}
+
+#[tauri::command]
+pub fn is_autostart_enabled(app: AppHandle) -> bool {
+    is_enabled(&app)
+}
@@ -0,0 +1,149 @@
+// Unread badge on the dock / taskbar / launcher entry.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+// Each platform only reports some of these
+#[allow(dead_code)]
+pub enum BadgeMechanism {
+    /// macOS dock badge with the count
+    Dock,
+    /// Windows taskbar overlay icon (a dot; overlays can't show a number)
+    Overlay,
+    /// Unity launcher count (GNOME dock, KDE, Plank, ...)
+    Launcher,
+    /// Nothing could be set; the frontend should fall back to the tray icon
+    None,
+}
+
+/// Show `count` unread messages on the app's dock/taskbar entry; 0 clears it.
+/// Returns which mechanism was used so the frontend can fall back to tray
+/// icon swapping where nothing is supported.
+#[tauri::command]
+pub fn set_badge_count(app: AppHandle, count: u32) -> Result<BadgeMechanism, String> {
+    crate::tray::set_unread_count(&app, count);
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let icon = if count > 0 {
+            Some(
+                tauri::image::Image::from_bytes(include_bytes!("../icons/overlay-unread.png"))
+                    .map_err(|e| e.to_string())?,
+            )
+        } else {
+            None
+        };
+        match window.set_overlay_icon(icon) {
+            Ok(()) => Ok(BadgeMechanism::Overlay),
+            Err(e) => {
+                log::warn!("failed to set taskbar overlay: {e}");
+                Ok(BadgeMechanism::None)
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let badge = (count > 0).then_some(count as i64);
+        match window.set_badge_count(badge) {
+            Ok(()) if cfg!(target_os = "macos") => Ok(BadgeMechanism::Dock),
+            Ok(()) if cfg!(target_os = "linux") => Ok(BadgeMechanism::Launcher),
+            Ok(()) => Ok(BadgeMechanism::None),
+            Err(e) => {
+                log::warn!("failed to set badge count: {e}");
+                Ok(BadgeMechanism::None)
+            }
+        }
+    }
+}
+
+/// Overlay icons bundled with the app, by name.
+const OVERLAY_ICONS: &[(&str, &[u8], &str)] = &[
+    ("unread", include_bytes!("../icons/overlay-unread.png"), "•"),
+    ("mention", include_bytes!("../icons/overlay-mention.png"), "@"),
+    ("dm", include_bytes!("../icons/overlay-dm.png"), "✉"),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlaySource {
+    /// One of `OVERLAY_ICONS`
+    Named(String),
+    /// PNG bytes
+    Png(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OverlayIcon {
+    #[serde(flatten)]
+    pub source: OverlaySource,
+    /// Dock badge text used instead on macOS. Named icons have a default.
+    pub label: Option<String>,
+}
+
+impl OverlayIcon {
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn image(&self) -> Result<tauri::image::Image<'static>, String> {
+        let bytes = match &self.source {
+            OverlaySource::Named(name) => OVERLAY_ICONS
+                .iter()
+                .find(|(n, _, _)| n == name)
+                .map(|(_, bytes, _)| *bytes)
+                .ok_or_else(|| format!("unknown overlay icon \"{name}\""))?,
+            OverlaySource::Png(bytes) => bytes,
+        };
+        tauri::image::Image::from_bytes(bytes)
+            .map(|image| image.to_owned())
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn label(&self) -> String {
+        self.label.clone().unwrap_or_else(|| match &self.source {
+            OverlaySource::Named(name) => OVERLAY_ICONS
+                .iter()
+                .find(|(n, _, _)| n == name)
+                .map_or("•", |(_, _, label)| label)
+                .to_string(),
+            OverlaySource::Png(_) => "•".to_string(),
+        })
+    }
+}
+
+/// Show a category-specific overlay on the taskbar entry (e.g. DMs vs
+/// mentions); `None` clears it. Replaces the overlay `set_badge_count` sets
+/// on Windows. macOS shows the icon's label as dock badge text instead, and
+/// Linux has no overlays.
+#[tauri::command]
+pub fn set_taskbar_overlay(
+    app: AppHandle,
+    icon: Option<OverlayIcon>,
+) -> Result<BadgeMechanism, String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let image = icon.as_ref().map(OverlayIcon::image).transpose()?;
+        window
+            .set_overlay_icon(image)
+            .map(|()| BadgeMechanism::Overlay)
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        window
+            .set_badge_label(icon.as_ref().map(OverlayIcon::label))
+            .map(|()| BadgeMechanism::Dock)
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = (window, icon);
+        Ok(BadgeMechanism::None)
+    }
+}
@@ -0,0 +1,195 @@
+// Disk usage and "Clear Cache". Clearing must never touch cookies,
+// localStorage/IndexedDB or our own settings, so it's careful about what
+// it removes:
+//
+// - The app cache dir only holds disposable data (the webview's HTTP cache
+//   on macOS/Linux, among others), so everything in it goes except what's
+//   listed in `KEEP_IN_CACHE_DIR`.
+// - WebView2 keeps its caches inside the profile next to the session data,
+//   so only the profile subdirectories in `WEBVIEW2_CACHE_DIRS` are removed.
+// - WebKitGTK is additionally asked to drop its caches itself, which also
+//   covers the in-memory cache of the running webview.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Entries of the app cache dir that aren't cache: a staged update.
+const KEEP_IN_CACHE_DIR: &[&str] = &["updates"];
+
+/// Cache subdirectories of a WebView2 user data folder (`EBWebView`).
+#[cfg(target_os = "windows")]
+const WEBVIEW2_CACHE_DIRS: &[&str] = &[
+    "Default/Cache",
+    "Default/Code Cache",
+    "Default/GPUCache",
+    "Default/DawnCache",
+    "Default/DawnGraphiteCache",
+    "Default/DawnWebGPUCache",
+    "Default/Service Worker/CacheStorage",
+    "GrShaderCache",
+    "GraphiteDawnCache",
+    "ShaderCache",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheUsage {
+    /// Everything under the app's data and cache dirs
+    pub total_bytes: u64,
+    /// What `clear_cache` would remove
+    pub cache_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct CacheCleared {
+    freed_bytes: u64,
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Remove `path`, returning how many bytes went. Files the webview still has
+/// open (WebView2 locks some) are skipped.
+fn remove(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return match std::fs::remove_file(path) {
+            Ok(()) => meta.len(),
+            Err(_) => 0,
+        };
+    }
+    let freed = std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| remove(&e.path())).sum())
+        .unwrap_or(0);
+    let _ = std::fs::remove_dir(path);
+    freed
+}
+
+/// Every path `clear_cache` removes.
+fn cache_paths<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(cache_dir) = crate::paths::cache_dir(app) {
+        if let Ok(entries) = std::fs::read_dir(&cache_dir) {
+            paths.extend(
+                entries
+                    .flatten()
+                    .filter(|e| !KEEP_IN_CACHE_DIR.iter().any(|k| e.file_name() == *k))
+                    .map(|e| e.path()),
+            );
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // The main profile, plus one per account window
+        let mut profiles = Vec::new();
+        if let Ok(dir) = crate::paths::local_data_dir(app) {
+            profiles.push(dir.join("EBWebView"));
+        }
+        if let Ok(dir) = crate::paths::data_dir(app) {
+            if let Ok(entries) = std::fs::read_dir(dir.join("accounts")) {
+                profiles.extend(entries.flatten().map(|e| e.path().join("EBWebView")));
+            }
+        }
+        for profile in profiles {
+            paths.extend(WEBVIEW2_CACHE_DIRS.iter().map(|d| profile.join(d)));
+        }
+    }
+
+    paths
+}
+
+fn usage<R: Runtime>(app: &AppHandle<R>) -> CacheUsage {
+    let mut roots: Vec<PathBuf> = [
+        crate::paths::data_dir(app),
+        crate::paths::local_data_dir(app),
+        crate::paths::cache_dir(app),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    roots.sort();
+    roots.dedup();
+    // Don't count a dir twice when one root contains another
+    let roots: Vec<&PathBuf> = roots
+        .iter()
+        .filter(|r| !roots.iter().any(|other| other != *r && r.starts_with(other)))
+        .collect();
+    CacheUsage {
+        total_bytes: roots.iter().map(|r| dir_size(r)).sum(),
+        cache_bytes: cache_paths(app).iter().map(|p| dir_size(p)).sum(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn clear_webkit_caches<R: Runtime>(app: &AppHandle<R>) {
+    for window in app.webview_windows().into_values() {
+        let _ = window.with_webview(|webview| {
+            use gtk::{gio, glib};
+            use webkit2gtk::{
+                WebContextExt, WebViewExt, WebsiteDataManagerExtManual, WebsiteDataTypes,
+            };
+            let Some(manager) = webview.inner().context().and_then(|c| c.website_data_manager()) else {
+                return;
+            };
+            manager.clear(
+                WebsiteDataTypes::DISK_CACHE
+                    | WebsiteDataTypes::MEMORY_CACHE
+                    | WebsiteDataTypes::OFFLINE_APPLICATION_CACHE,
+                glib::TimeSpan(0),
+                None::<&gio::Cancellable>,
+                |result| {
+                    if let Err(e) = result {
+                        log::warn!("failed to clear webview cache: {e}");
+                    }
+                },
+            );
+        });
+    }
+}
+
+/// Disk used by the app, and how much of it is clearable cache.
+#[tauri::command]
+pub async fn get_cache_size(app: AppHandle) -> Result<CacheUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || usage(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete the webview and app caches, keeping sessions and settings, then
+/// emit `cache-cleared { freed_bytes }`. With `reload`, the main window is
+/// reloaded afterwards so it doesn't keep serving from memory. Returns the
+/// number of bytes freed.
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle, reload: Option<bool>) -> Result<u64, String> {
+    #[cfg(target_os = "linux")]
+    clear_webkit_caches(&app);
+
+    let app_for_delete = app.clone();
+    let freed = tauri::async_runtime::spawn_blocking(move || {
+        cache_paths(&app_for_delete).iter().map(|p| remove(p)).sum::<u64>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    log::info!("cleared {freed} bytes of cache");
+
+    let _ = app.emit("cache-cleared", CacheCleared { freed_bytes: freed });
+    if reload.unwrap_or(false) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.eval("window.location.reload()");
+        }
+    }
+    Ok(freed)
+}
@@ -0,0 +1,169 @@
+// Screen and window sources for the screen-share picker, and screenshots
+// of the Nexus window itself for bug reports.
+
+use std::io::Cursor;
+
+use base64::Engine;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use xcap::image::{DynamicImage, ImageFormat, RgbaImage};
+
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 180;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureKind {
+    Screen,
+    Window,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSource {
+    pub id: String,
+    pub kind: CaptureKind,
+    pub title: String,
+    pub thumbnail_png: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CaptureSources {
+    pub sources: Vec<CaptureSource>,
+    /// Set on Wayland, where sources can't be enumerated directly; the
+    /// frontend should go through the xdg-desktop-portal picker instead.
+    pub portal_required: bool,
+}
+
+fn is_wayland() -> bool {
+    cfg!(target_os = "linux")
+        && (std::env::var_os("WAYLAND_DISPLAY").is_some()
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland"))
+}
+
+fn thumbnail(image: RgbaImage) -> Vec<u8> {
+    let thumb = DynamicImage::ImageRgba8(image).thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    let mut out = Cursor::new(Vec::new());
+    // A source without a thumbnail is still shareable
+    let _ = thumb.write_to(&mut out, ImageFormat::Png);
+    out.into_inner()
+}
+
+fn enumerate() -> CaptureSources {
+    if is_wayland() {
+        return CaptureSources {
+            sources: Vec::new(),
+            portal_required: true,
+        };
+    }
+
+    let mut sources = Vec::new();
+    for monitor in xcap::Monitor::all().unwrap_or_default() {
+        let Ok(id) = monitor.id() else {
+            continue;
+        };
+        sources.push(CaptureSource {
+            id: format!("screen:{id}"),
+            kind: CaptureKind::Screen,
+            title: monitor.name().unwrap_or_else(|_| format!("Screen {id}")),
+            thumbnail_png: monitor.capture_image().map(thumbnail).unwrap_or_default(),
+        });
+    }
+    for window in xcap::Window::all().unwrap_or_default() {
+        if window.is_minimized().unwrap_or(false) {
+            continue;
+        }
+        let (Ok(id), Ok(title)) = (window.id(), window.title()) else {
+            continue;
+        };
+        // Untitled windows are mostly tooltips, overlays and other chrome
+        if title.is_empty() {
+            continue;
+        }
+        sources.push(CaptureSource {
+            id: format!("window:{id}"),
+            kind: CaptureKind::Window,
+            title,
+            thumbnail_png: window.capture_image().map(thumbnail).unwrap_or_default(),
+        });
+    }
+    CaptureSources {
+        sources,
+        portal_required: false,
+    }
+}
+
+/// The window's native id in the form xcap reports it: the HWND on
+/// Windows, the CGWindowID on macOS and the X11 window id on Linux.
+#[cfg(target_os = "windows")]
+fn native_id(window: &WebviewWindow) -> Result<u32, String> {
+    Ok(window.hwnd().map_err(|e| e.to_string())?.0 as usize as u32)
+}
+
+#[cfg(target_os = "macos")]
+fn native_id(window: &WebviewWindow) -> Result<u32, String> {
+    use objc2_app_kit::NSWindow;
+
+    let ptr = window.ns_window().map_err(|e| e.to_string())?;
+    // SAFETY: tauri hands back the window's live NSWindow
+    let ns_window = unsafe { &*(ptr as *const NSWindow) };
+    Ok(ns_window.windowNumber() as u32)
+}
+
+#[cfg(target_os = "linux")]
+fn native_id(window: &WebviewWindow) -> Result<u32, String> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    match window.window_handle().map_err(|e| e.to_string())?.as_raw() {
+        RawWindowHandle::Xlib(handle) => Ok(handle.window as u32),
+        _ => Err("the Nexus window isn't an X11 window".into()),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn native_id(_window: &WebviewWindow) -> Result<u32, String> {
+    Err("window screenshots aren't supported on this platform".into())
+}
+
+/// Capture the window with native id `id`.
+fn capture_own_window(id: u32) -> Result<Vec<u8>, String> {
+    let window = xcap::Window::all()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|w| w.id().is_ok_and(|w_id| w_id == id))
+        .ok_or("window not found")?;
+    let image = window.capture_image().map_err(|e| e.to_string())?;
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(out.into_inner())
+}
+
+/// List monitors and open windows that can be shared, with small thumbnails.
+#[tauri::command]
+pub async fn list_capture_sources() -> Result<CaptureSources, String> {
+    tauri::async_runtime::spawn_blocking(enumerate)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Screenshot of just the main window, as base64-encoded PNG. Fails if the
+/// window is minimized or hidden, since there's nothing to capture.
+#[tauri::command]
+pub async fn capture_window_screenshot(app: AppHandle) -> Result<String, String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+    if window.is_minimized().unwrap_or(false) {
+        return Err("the Nexus window is minimized".into());
+    }
+    if !window.is_visible().unwrap_or(true) {
+        return Err("the Nexus window is hidden".into());
+    }
+    if is_wayland() {
+        return Err("window screenshots aren't available on Wayland".into());
+    }
+    let id = native_id(&window)?;
+    let png = tauri::async_runtime::spawn_blocking(move || capture_own_window(id))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
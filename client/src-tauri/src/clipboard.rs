@@ -0,0 +1,55 @@
+// Clipboard reads for pasting screenshots into the chat box. Reads run on a
+// blocking task: converting a large screenshot to PNG takes long enough to
+// stall the UI thread otherwise.
+
+use base64::Engine;
+
+fn encode_png(image: arboard::ImageData) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(&image.bytes)
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+fn read_image() -> Result<Option<String>, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        // Empty clipboard, or it holds something other than an image
+        Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let png = encode_png(image)?;
+    Ok(Some(base64::engine::general_purpose::STANDARD.encode(png)))
+}
+
+fn read_text() -> Result<Option<String>, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    match clipboard.get_text() {
+        Ok(text) => Ok(Some(text)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The clipboard image as base64-encoded PNG, or `None` if the clipboard
+/// doesn't hold an image.
+#[tauri::command]
+pub async fn read_clipboard_image() -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(read_image)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn read_clipboard_text() -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(read_text)
+        .await
+        .map_err(|e| e.to_string())?
+}
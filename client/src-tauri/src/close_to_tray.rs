@@ -0,0 +1,60 @@
+// "Close to tray": closing the main window hides it instead of quitting, so
+// voice connections survive. Quitting goes through the tray or File > Quit.
+
+use tauri::menu::CheckMenuItem;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::SettingsStore;
+
+/// Handle to the File menu's "Minimize to Tray" check item so the command
+/// can keep it in sync.
+pub struct CloseToTrayMenuItem(pub CheckMenuItem<Wry>);
+
+pub fn is_enabled<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.state::<SettingsStore>().get().close_to_tray
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) {
+    app.state::<SettingsStore>()
+        .update(app, |s| s.close_to_tray = enabled);
+    if let Some(item) = app.try_state::<CloseToTrayMenuItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+/// Hide the window to the tray. The first time this happens, tell the user
+/// the app is still running so they don't think it crashed or quit.
+pub fn hide_to_tray<R: Runtime>(window: &WebviewWindow<R>) {
+    // On macOS, hide the app (not just the window) so clicking
+    // the dock icon automatically restores it via the native
+    // applicationShouldHandleReopen delegate
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::MainThreadMarker;
+        use objc2_app_kit::NSApplication;
+        let mtm = unsafe { MainThreadMarker::new_unchecked() };
+        let ns_app = NSApplication::sharedApplication(mtm);
+        ns_app.hide(None);
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.hide();
+
+    let app = window.app_handle();
+    crate::window_events::refresh(app);
+    let store = app.state::<SettingsStore>();
+    if !store.get().close_to_tray_notified {
+        store.update(app, |s| s.close_to_tray_notified = true);
+        let _ = app
+            .notification()
+            .builder()
+            .title("Nexus is still running")
+            .body("Nexus keeps running in the system tray. Use Quit from the tray menu to exit.")
+            .show();
+    }
+}
+
+#[tauri::command]
+pub fn set_close_to_tray(app: AppHandle, enabled: bool) {
+    set_enabled(&app, enabled);
+}
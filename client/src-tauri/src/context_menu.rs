@@ -0,0 +1,173 @@
+// Native right-click menu. The frontend handles `contextmenu` in the page and
+// calls `show_context_menu` with the items to show and an opaque `context`
+// (e.g. the targeted message id), which is echoed back in
+// `context-menu-clicked` when an item is chosen.
+//
+// A `spelling_suggestions` item expands to the spell checker's corrections
+// for the misspelled word under the cursor (see `spellcheck::suggestions`).
+// Choosing one is reported with the id `spelling-suggestion` and the
+// correction as `suggestion`, for the frontend to replace the word with.
+//
+// On Linux the stock WebKit menu is also trimmed of browser-only entries
+// (back/forward, open in new window, downloads) for any right-click the
+// frontend doesn't handle itself.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder, PredefinedMenuItem};
+use tauri::{AppHandle, Emitter, LogicalPosition, Manager, Runtime, WebviewWindow};
+
+/// Prefix for context menu item ids, so the app menu handler can route them.
+pub const ID_PREFIX: &str = "ctx:";
+
+/// Marks a spelling suggestion's id, after `ID_PREFIX`.
+const SUGGESTION_PREFIX: &str = "spelling-suggestion:";
+const SUGGESTION_ID: &str = "spelling-suggestion";
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContextItem {
+    Item {
+        id: String,
+        label: String,
+        #[serde(default = "enabled_default")]
+        enabled: bool,
+    },
+    Separator,
+    SpellingSuggestions {
+        word: String,
+    },
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+/// Window label and context of the menu currently shown.
+#[derive(Default)]
+pub struct ContextMenuState(Mutex<Option<(String, serde_json::Value)>>);
+
+#[derive(Clone, Serialize)]
+struct ContextMenuClicked {
+    id: String,
+    context: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<String>,
+}
+
+fn build_menu<R: Runtime>(app: &AppHandle<R>, items: &[ContextItem]) -> tauri::Result<Menu<R>> {
+    let mut builder = MenuBuilder::new(app);
+    for item in items {
+        builder = match item {
+            ContextItem::Item { id, label, enabled } => builder.item(
+                &MenuItemBuilder::with_id(format!("{ID_PREFIX}{id}"), label)
+                    .enabled(*enabled)
+                    .build(app)?,
+            ),
+            ContextItem::Separator => builder.separator(),
+            ContextItem::SpellingSuggestions { word } => {
+                let suggestions = crate::spellcheck::suggestions(app, word, MAX_SUGGESTIONS);
+                if suggestions.is_empty() {
+                    let none = MenuItemBuilder::new("No Suggestions").enabled(false).build(app)?;
+                    builder.item(&none)
+                } else {
+                    for suggestion in suggestions {
+                        let id = format!("{ID_PREFIX}{SUGGESTION_PREFIX}{suggestion}");
+                        builder = builder.item(&MenuItemBuilder::with_id(id, &suggestion).build(app)?);
+                    }
+                    builder
+                }
+            }
+            ContextItem::Cut => builder.item(&PredefinedMenuItem::cut(app, None)?),
+            ContextItem::Copy => builder.item(&PredefinedMenuItem::copy(app, None)?),
+            ContextItem::Paste => builder.item(&PredefinedMenuItem::paste(app, None)?),
+            ContextItem::SelectAll => builder.item(&PredefinedMenuItem::select_all(app, None)?),
+        };
+    }
+    builder.build()
+}
+
+/// App menu handler hook for `ctx:` ids.
+pub fn handle_click<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    let Some(id) = id.strip_prefix(ID_PREFIX) else {
+        return;
+    };
+    let Some((label, context)) = app.state::<ContextMenuState>().0.lock().unwrap().take() else {
+        return;
+    };
+    let (id, suggestion) = match id.strip_prefix(SUGGESTION_PREFIX) {
+        Some(suggestion) => (SUGGESTION_ID.to_string(), Some(suggestion.to_string())),
+        None => (id.to_string(), None),
+    };
+    let _ = app.emit_to(
+        label.as_str(),
+        "context-menu-clicked",
+        ContextMenuClicked {
+            id,
+            context,
+            suggestion,
+        },
+    );
+}
+
+/// Strip browser navigation entries from WebKit's default context menu.
+#[cfg(target_os = "linux")]
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    window.with_webview(|webview| {
+        use webkit2gtk::{ContextMenuAction, ContextMenuExt, ContextMenuItemExt, WebViewExt};
+        webview.inner().connect_context_menu(|_wv, menu, _event, _hit| {
+            for item in menu.items() {
+                let hide = matches!(
+                    item.stock_action(),
+                    ContextMenuAction::GoBack
+                        | ContextMenuAction::GoForward
+                        | ContextMenuAction::Stop
+                        | ContextMenuAction::Reload
+                        | ContextMenuAction::OpenLinkInNewWindow
+                        | ContextMenuAction::DownloadLinkToDisk
+                        | ContextMenuAction::OpenImageInNewWindow
+                        | ContextMenuAction::DownloadImageToDisk
+                        | ContextMenuAction::OpenFrameInNewWindow
+                        | ContextMenuAction::OpenVideoInNewWindow
+                        | ContextMenuAction::OpenAudioInNewWindow
+                        | ContextMenuAction::DownloadVideoToDisk
+                        | ContextMenuAction::DownloadAudioToDisk
+                );
+                if hide {
+                    menu.remove(&item);
+                }
+            }
+            // false = still show the (trimmed) menu
+            false
+        });
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn init<R: Runtime>(_window: &WebviewWindow<R>) -> tauri::Result<()> {
+    Ok(())
+}
+
+/// Show a native context menu at `(x, y)` in logical window coordinates.
+#[tauri::command]
+pub fn show_context_menu(
+    window: WebviewWindow,
+    x: f64,
+    y: f64,
+    items: Vec<ContextItem>,
+    context: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let app = window.app_handle();
+    let menu = build_menu(app, &items).map_err(|e| e.to_string())?;
+    *app.state::<ContextMenuState>().0.lock().unwrap() =
+        Some((window.label().to_string(), context.unwrap_or_default()));
+    window
+        .popup_menu_at(&menu, LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,157 @@
+// Crash reporting. A panic hook writes a report (message, backtrace, app
+// version, OS) to the log dir. On the next launch pending reports are
+// announced with `crash-report-pending` so the frontend can offer to submit
+// them via `submit_crash_report`.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::settings::SettingsStore;
+
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_EXT: &str = "txt";
+
+/// Where the panic hook writes reports. Starts as a best guess computed
+/// before the app exists and is replaced with the real log dir in setup.
+static CRASH_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+fn crash_dir() -> Option<PathBuf> {
+    // Never panic inside the panic hook, even if a previous panic poisoned
+    // the lock
+    CRASH_DIR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Takes the panic's parts rather than the hook's argument, whose type was
+/// renamed after our MSRV.
+fn format_report(
+    payload: &(dyn std::any::Any + Send),
+    location: Option<&std::panic::Location<'_>>,
+    version: &str,
+) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".into());
+    let location = location
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "<unknown>".into());
+    let thread = std::thread::current();
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format!(
+        "Nexus crash report\n\
+         version: {version}\n\
+         os: {} {}\n\
+         timestamp: {timestamp}\n\
+         thread: {}\n\
+         location: {location}\n\
+         message: {message}\n\n\
+         backtrace:\n{}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        thread.name().unwrap_or("<unnamed>"),
+        std::backtrace::Backtrace::force_capture(),
+    )
+}
+
+/// Install the panic hook. Called at the top of `run()`.
+pub fn install_panic_hook(identifier: &str, version: String) {
+    *CRASH_DIR.lock().unwrap_or_else(PoisonError::into_inner) = crate::paths::early_log_dir(identifier);
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(dir) = crash_dir() {
+            let report = format_report(info.payload(), info.location(), &version);
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default();
+            let _ = std::fs::create_dir_all(&dir);
+            let _ = std::fs::write(dir.join(format!("{REPORT_PREFIX}{timestamp}.{REPORT_EXT}")), report);
+        }
+        previous(info);
+    }));
+}
+
+fn is_report(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == REPORT_EXT)
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(REPORT_PREFIX))
+}
+
+fn pending_reports() -> Vec<PathBuf> {
+    let Some(dir) = crash_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_report(p))
+        .collect();
+    reports.sort();
+    reports
+}
+
+#[derive(Clone, Serialize)]
+struct CrashReportPending {
+    path: PathBuf,
+}
+
+/// Point the hook at the real log dir.
+pub fn init(app: &tauri::App) {
+    if let Ok(dir) = crate::paths::log_dir(app) {
+        *CRASH_DIR.lock().unwrap_or_else(PoisonError::into_inner) = Some(dir);
+    }
+}
+
+/// Announce reports left by a previous crash. Called once the frontend has
+/// loaded, so the events aren't emitted before anything listens.
+pub fn announce_pending<R: Runtime>(app: &AppHandle<R>) {
+    for path in pending_reports() {
+        let _ = app.emit("crash-report-pending", CrashReportPending { path });
+    }
+}
+
+#[tauri::command]
+pub fn get_pending_crash_reports() -> Vec<PathBuf> {
+    pending_reports()
+}
+
+/// POST a report to the configured endpoint and delete it on success.
+#[tauri::command]
+pub async fn submit_crash_report(app: AppHandle, path: PathBuf) -> Result<(), String> {
+    // Only ever read (and delete) our own reports
+    if !pending_reports().contains(&path) {
+        return Err("not a pending crash report".into());
+    }
+    let settings = app.state::<SettingsStore>().get();
+    let endpoint = settings
+        .crash_report_endpoint
+        .ok_or("no crash report endpoint configured")?;
+    let body = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+    crate::http::client(&app)?
+        .post(endpoint)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    std::fs::remove_file(&path).map_err(|e| e.to_string())
+}
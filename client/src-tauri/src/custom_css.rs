@@ -0,0 +1,210 @@
+// Server theme packs: CSS injected by the webview itself rather than by the
+// page, so it survives reloads and is in place before the first paint.
+//
+// - Linux: a WebKit user style sheet on the webview's UserContentManager.
+// - Windows: a WebView2 document-created script that adds a <style> element.
+//   Its id is kept so the script can be swapped out later.
+// - macOS: the same script as a WKUserScript at document start. WebKit can't
+//   remove a single user script, so replacing or clearing the CSS adds
+//   another script that runs after the old ones and overrides them.
+//
+// The CSS is saved per window label, so each account window keeps its
+// server's theme, and is reapplied by `init` when the window is created.
+
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[cfg(not(target_os = "linux"))]
+const STYLE_ELEMENT_ID: &str = "nexus-injected-css";
+
+/// Ids of the WebView2 scripts currently injecting CSS, by window label.
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+pub struct InjectedCssScripts(Mutex<HashMap<String, String>>);
+
+/// Script that sets (or with `None`, removes) the injected <style> element.
+#[cfg(not(target_os = "linux"))]
+fn script(css: Option<&str>) -> String {
+    match css {
+        Some(css) => {
+            let css = serde_json::to_string(css).unwrap_or_default();
+            format!(
+                r#"(() => {{
+  let el = document.getElementById("{STYLE_ELEMENT_ID}");
+  if (!el) {{
+    el = document.createElement("style");
+    el.id = "{STYLE_ELEMENT_ID}";
+    (document.head || document.documentElement).appendChild(el);
+  }}
+  el.textContent = {css};
+}})();"#
+            )
+        }
+        None => format!(r#"document.getElementById("{STYLE_ELEMENT_ID}")?.remove();"#),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply<R: Runtime>(window: &WebviewWindow<R>, css: Option<&str>) -> Result<(), String> {
+    let css = css.map(str::to_string);
+    window
+        .with_webview(move |webview| {
+            use webkit2gtk::{
+                UserContentInjectedFrames, UserContentManagerExt, UserStyleLevel, UserStyleSheet,
+                WebViewExt,
+            };
+
+            let Some(manager) = webview.inner().user_content_manager() else {
+                return;
+            };
+            manager.remove_all_style_sheets();
+            if let Some(css) = css {
+                manager.add_style_sheet(&UserStyleSheet::new(
+                    &css,
+                    UserContentInjectedFrames::AllFrames,
+                    UserStyleLevel::User,
+                    &[],
+                    &[],
+                ));
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn apply<R: Runtime>(window: &WebviewWindow<R>, css: Option<&str>) -> Result<(), String> {
+    let source = script(css);
+    let install = css.is_some().then(|| source.clone());
+    let label = window.label().to_string();
+    let app = window.app_handle().clone();
+    let previous = app
+        .state::<InjectedCssScripts>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(&label);
+    window
+        .with_webview(move |webview| unsafe {
+            use webview2_com::AddScriptToExecuteOnDocumentCreatedCompletedHandler;
+            use windows::core::HSTRING;
+
+            let core = webview.controller().CoreWebView2().unwrap();
+            if let Some(id) = previous {
+                let _ = core.RemoveScriptToExecuteOnDocumentCreated(&HSTRING::from(id));
+            }
+            let Some(install) = install else {
+                return;
+            };
+            let handler = AddScriptToExecuteOnDocumentCreatedCompletedHandler::create(Box::new(
+                move |result, id| {
+                    match result {
+                        Ok(()) => {
+                            app.state::<InjectedCssScripts>()
+                                .0
+                                .lock()
+                                .unwrap()
+                                .insert(label, id);
+                        }
+                        Err(e) => log::warn!("failed to inject css: {e}"),
+                    }
+                    Ok(())
+                },
+            ));
+            if let Err(e) =
+                core.AddScriptToExecuteOnDocumentCreated(&HSTRING::from(install), &handler)
+            {
+                log::warn!("failed to inject css: {e}");
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    // The script only runs on the next document; update the current one too
+    window.eval(&source).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn apply<R: Runtime>(window: &WebviewWindow<R>, css: Option<&str>) -> Result<(), String> {
+    let source = script(css);
+    let user_script_source = source.clone();
+    window
+        .with_webview(move |webview| unsafe {
+            use objc2::rc::{Allocated, Retained};
+            use objc2::runtime::{AnyClass, AnyObject};
+            use objc2::msg_send;
+            use objc2_foundation::NSString;
+
+            // WKUserScriptInjectionTimeAtDocumentStart
+            const AT_DOCUMENT_START: isize = 0;
+
+            let Some(class) = AnyClass::get(c"WKUserScript") else {
+                return;
+            };
+            // SAFETY: `inner` is the live WKWebView
+            let wv = &*(webview.inner() as *const AnyObject);
+            let config: *mut AnyObject = msg_send![wv, configuration];
+            let controller: *mut AnyObject = msg_send![config, userContentController];
+            let source = NSString::from_str(&user_script_source);
+            let allocated: Allocated<AnyObject> = msg_send![class, alloc];
+            let user_script: Retained<AnyObject> = msg_send![
+                allocated,
+                initWithSource: &*source,
+                injectionTime: AT_DOCUMENT_START,
+                forMainFrameOnly: true
+            ];
+            let _: () = msg_send![controller, addUserScript: &*user_script];
+        })
+        .map_err(|e| e.to_string())?;
+    // User scripts only run on the next document; update the current one too
+    window.eval(&source).map_err(|e| e.to_string())
+}
+
+/// Reapply the window's saved CSS. Call once per window, right after it's
+/// built. Skipped in safe mode.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    if crate::safe_mode::active() {
+        return;
+    }
+    let saved = window
+        .app_handle()
+        .state::<SettingsStore>()
+        .get()
+        .injected_css
+        .remove(window.label());
+    if let Some(css) = saved {
+        if let Err(e) = apply(window, Some(&css)) {
+            log::warn!("failed to reapply injected css: {e}");
+        }
+    }
+}
+
+fn save(app: &AppHandle, label: &str, css: Option<String>) {
+    app.state::<SettingsStore>().update(app, |s| match css {
+        Some(css) => {
+            s.injected_css.insert(label.to_string(), css);
+        }
+        None => {
+            s.injected_css.remove(label);
+        }
+    });
+}
+
+/// Replace the calling window's injected CSS. It stays applied across
+/// reloads and restarts until `clear_injected_css`.
+#[tauri::command]
+pub fn inject_css(app: AppHandle, window: WebviewWindow, css: String) -> Result<(), String> {
+    apply(&window, Some(&css))?;
+    save(&app, window.label(), Some(css));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_injected_css(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    apply(&window, None)?;
+    save(&app, window.label(), None);
+    Ok(())
+}
@@ -0,0 +1,199 @@
+// nexus:// deep links. Links are forwarded to the frontend as `open-deep-link`
+// events once it's ready for them. Until then they're buffered, and the
+// frontend pulls them with `deep_link_ready` after attaching its listener,
+// so nothing sent before that is lost. A new page load in the main window
+// (a reload) starts buffering again until the new page pulls.
+//
+// The screen to start on is returned once, with the first pull. It comes
+// from the link that launched the app, translated to a route
+// (`nexus://channels/123` → `/channels/123`), or else from
+// `--route /channels/123` on the command line.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "nexus";
+
+const ROUTE_ARG: &str = "--route";
+
+#[derive(Default)]
+struct DeepLinkInner {
+    ready: bool,
+    pending: Vec<String>,
+    initial_route: Option<String>,
+}
+
+#[derive(Default)]
+pub struct DeepLinkState(Mutex<DeepLinkInner>);
+
+/// What the frontend missed before it was ready.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PendingDeepLinks {
+    pub initial_route: Option<String>,
+    pub links: Vec<String>,
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.strip_prefix(SCHEME).is_some_and(|rest| rest.starts_with("://"))
+}
+
+/// `--route <path>` / `--route=<path>` from the command line.
+pub fn route_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let route = if arg == ROUTE_ARG {
+            iter.next().cloned()
+        } else {
+            arg.strip_prefix("--route=").map(str::to_string)
+        };
+        if let Some(route) = route.filter(|r| r.starts_with('/')) {
+            return Some(route);
+        }
+    }
+    None
+}
+
+/// The app route a deep link points at: its host and path, plus any query,
+/// e.g. `nexus://invite/abc?ref=x` → `/invite/abc?ref=x`.
+fn route_from_link(link: &str) -> Option<String> {
+    let url = Url::parse(link).ok()?;
+    let host = url.host_str().filter(|h| !h.is_empty())?;
+    let mut route = format!("/{host}{}", url.path().trim_end_matches('/'));
+    if let Some(query) = url.query() {
+        route.push('?');
+        route.push_str(query);
+    }
+    Some(route)
+}
+
+/// Emit a link to the frontend, or hold it until the frontend is ready.
+pub fn deliver<R: Runtime>(app: &AppHandle<R>, url: String) {
+    let Some(state) = app.try_state::<DeepLinkState>() else {
+        return;
+    };
+    let mut inner = state.0.lock().unwrap();
+    if inner.ready {
+        let _ = app.emit("open-deep-link", url);
+    } else {
+        inner.pending.push(url);
+    }
+}
+
+/// `cli_route` is `route_from_args` of the launch arguments.
+pub fn init(app: &tauri::App, cli_route: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    app.manage(DeepLinkState(Mutex::new(DeepLinkInner {
+        initial_route: cli_route,
+        ..Default::default()
+    })));
+
+    // macOS registers schemes from the bundle's Info.plist; Linux and Windows
+    // need the handler registered at runtime (also covers dev builds)
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    app.deep_link().register(SCHEME)?;
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            deliver(&handle, url.to_string());
+        }
+    });
+
+    // Cold start: the app was launched by clicking a link, which beats a
+    // --route argument for where to start. That link is then the initial
+    // route rather than a link to open as well
+    if let Some(urls) = app.deep_link().get_current()? {
+        let mut urls = urls.into_iter().peekable();
+        if let Some(route) = urls.peek().and_then(|u| route_from_link(u.as_str())) {
+            app.state::<DeepLinkState>().0.lock().unwrap().initial_route = Some(route);
+            urls.next();
+        }
+        for url in urls {
+            deliver(app.handle(), url.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// A new page is loading in the main window, which has to pull links again
+/// before they're emitted to it.
+pub fn main_window_unloaded<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<DeepLinkState>() {
+        state.0.lock().unwrap().ready = false;
+    }
+}
+
+/// Called by the frontend once its `open-deep-link` listener is attached.
+/// Returns the initial route (the first time only) and the links received
+/// since the page loaded; later links arrive as events.
+#[tauri::command]
+pub fn deep_link_ready(app: AppHandle) -> PendingDeepLinks {
+    let state = app.state::<DeepLinkState>();
+    let mut inner = state.0.lock().unwrap();
+    inner.ready = true;
+    PendingDeepLinks {
+        initial_route: inner.initial_route.take(),
+        links: std::mem::take(&mut inner.pending),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn route_from_link_keeps_host_path_and_query() {
+        assert_eq!(route_from_link("nexus://channels/123").as_deref(), Some("/channels/123"));
+        assert_eq!(
+            route_from_link("nexus://invite/abc?ref=x").as_deref(),
+            Some("/invite/abc?ref=x")
+        );
+    }
+
+    #[test]
+    fn route_from_link_drops_trailing_slash() {
+        assert_eq!(route_from_link("nexus://settings/").as_deref(), Some("/settings"));
+        assert_eq!(route_from_link("nexus://settings").as_deref(), Some("/settings"));
+    }
+
+    #[test]
+    fn route_from_link_rejects_links_without_host() {
+        assert_eq!(route_from_link("nexus:///channels/123"), None);
+        assert_eq!(route_from_link("not a link"), None);
+    }
+
+    #[test]
+    fn route_from_args_accepts_both_forms() {
+        assert_eq!(
+            route_from_args(&args(&["nexus", "--route", "/channels/1"])).as_deref(),
+            Some("/channels/1")
+        );
+        assert_eq!(
+            route_from_args(&args(&["nexus", "--route=/channels/2"])).as_deref(),
+            Some("/channels/2")
+        );
+    }
+
+    #[test]
+    fn route_from_args_skips_relative_and_missing_routes() {
+        assert_eq!(route_from_args(&args(&["nexus", "--route", "channels"])), None);
+        assert_eq!(route_from_args(&args(&["nexus", "--route"])), None);
+        assert_eq!(
+            route_from_args(&args(&["--route=x", "--route", "/ok"])).as_deref(),
+            Some("/ok")
+        );
+    }
+
+    #[test]
+    fn is_deep_link_needs_the_scheme_separator() {
+        assert!(is_deep_link("nexus://channels/1"));
+        assert!(!is_deep_link("nexus:channels"));
+        assert!(!is_deep_link("nexusapp://x"));
+    }
+}
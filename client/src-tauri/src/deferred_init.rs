@@ -0,0 +1,46 @@
+// Background work that isn't needed for the first paint: the pollers behind
+// idle, network, device, power, accessibility and locale events, and the
+// startup update check. It starts once the main window has finished loading,
+// so it doesn't compete with the webview while it starts up;
+// `FALLBACK_DELAY` starts it anyway if that load never finishes.
+//
+// Only the threads wait. Any state the modules' commands need is managed in
+// `setup`, so the frontend can call them right away.
+
+use std::sync::Once;
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+
+const FALLBACK_DELAY: Duration = Duration::from_secs(10);
+
+static STARTED: Once = Once::new();
+
+/// Start everything deferred. Only the first call does anything.
+pub fn run<R: Runtime>(app: &AppHandle<R>) {
+    STARTED.call_once(|| {
+        crate::idle::start(app);
+        crate::network::start(app);
+        crate::audio::init(app);
+        crate::displays::init(app);
+        crate::mic_mute::init(app);
+        crate::power::init(app);
+        crate::accessibility::init(app);
+        crate::locale::init(app);
+        crate::updater::spawn_startup_check(app);
+        crate::startup_timing::mark("deferred init started");
+    });
+}
+
+/// Make sure `run` happens even without a finished page load. Called from
+/// `setup`.
+pub fn schedule_fallback<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FALLBACK_DELAY).await;
+        if !STARTED.is_completed() {
+            log::warn!("main window hasn't loaded after {FALLBACK_DELAY:?}, starting background work");
+            run(&app);
+        }
+    });
+}
@@ -0,0 +1,22 @@
+// Devtools policy. Debug builds always have them; release builds only when
+// started with NEXUS_ENABLE_DEVTOOLS=1, so support can debug a production
+// install without exposing devtools to everyone. Read once at startup.
+
+use std::sync::OnceLock;
+
+const ENABLE_ENV: &str = "NEXUS_ENABLE_DEVTOOLS";
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Whether webviews are built with devtools. Pass to every
+/// `WebviewWindowBuilder::devtools`.
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| {
+        cfg!(debug_assertions) || std::env::var(ENABLE_ENV).is_ok_and(|v| v == "1")
+    })
+}
+
+#[tauri::command]
+pub fn devtools_available() -> bool {
+    enabled()
+}
@@ -0,0 +1,74 @@
+// Free space on the filesystem holding a path, so large downloads can be
+// refused up front instead of failing halfway.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sysinfo::Disks;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DiskSpace {
+    pub total: u64,
+    pub available: u64,
+}
+
+/// The deepest existing ancestor of `path`, resolved, in the form the disk
+/// list uses.
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let resolved = path.ancestors().find_map(|p| p.canonicalize().ok())?;
+    // canonicalize gives verbatim paths (\\?\C:\...) on Windows, which don't
+    // compare equal to the drive-letter mount points
+    #[cfg(target_os = "windows")]
+    if let Some(plain) = resolved.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        return Some(PathBuf::from(plain));
+    }
+    Some(resolved)
+}
+
+/// Space on the filesystem `path` is on. The path itself needn't exist yet.
+pub fn for_path(path: &Path) -> Result<DiskSpace, String> {
+    let resolved =
+        existing_ancestor(path).ok_or_else(|| format!("{} doesn't exist", path.display()))?;
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|d| resolved.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().components().count())
+        .map(|d| DiskSpace {
+            total: d.total_space(),
+            available: d.available_space(),
+        })
+        .ok_or_else(|| format!("no disk found for {}", resolved.display()))
+}
+
+/// Refuse with an `insufficient_space` error unless `needed` bytes fit on
+/// the filesystem holding `path`. Passes if the space can't be determined.
+pub fn ensure_available(path: &Path, needed: u64, what: &str) -> Result<(), String> {
+    match for_path(path) {
+        Ok(space) if space.available < needed => Err(format!(
+            "insufficient_space: {what} needs {:.1} MB but only {:.1} MB is free",
+            needed as f64 / 1_000_000.0,
+            space.available as f64 / 1_000_000.0,
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            log::debug!("couldn't check free space: {e}");
+            Ok(())
+        }
+    }
+}
+
+/// Total and available bytes on the filesystem holding `path`, or the app
+/// data dir when it's omitted.
+#[tauri::command]
+pub async fn get_disk_space(app: AppHandle, path: Option<String>) -> Result<DiskSpace, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => crate::paths::data_dir(&app).map_err(|e| e.to_string())?,
+    };
+    tauri::async_runtime::spawn_blocking(move || for_path(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,86 @@
+// Monitor geometry for screen share and PiP placement. There's no
+// cross-platform "monitors changed" event, so a background thread polls the
+// monitor list and emits `displays-changed` with the new list whenever it
+// differs (a display connected or disconnected, moved, or rescaled).
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Display {
+    pub name: Option<String>,
+    /// Top-left corner in physical pixels, in desktop coordinates
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+fn display(monitor: &Monitor, primary: Option<&Monitor>) -> Display {
+    Display {
+        name: monitor.name().cloned(),
+        position: *monitor.position(),
+        size: *monitor.size(),
+        scale_factor: monitor.scale_factor(),
+        is_primary: primary.is_some_and(|p| {
+            p.name() == monitor.name() && p.position() == monitor.position()
+        }),
+    }
+}
+
+fn displays<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<Display>, String> {
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+    Ok(app
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|m| display(m, primary.as_ref()))
+        .collect())
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    let mut last = displays(&app).unwrap_or_default();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Ok(current) = displays(&app) else {
+            continue;
+        };
+        if current != last {
+            log::info!("displays changed: {} connected", current.len());
+            let _ = app.emit("displays-changed", &current);
+            last = current;
+        }
+    }
+}
+
+/// Start watching for display changes. Started by `deferred_init`.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("display-monitor".into())
+        .spawn(move || poll_loop(handle))
+    {
+        log::warn!("failed to start display monitor: {e}");
+    }
+}
+
+/// Every connected monitor.
+#[tauri::command]
+pub fn list_displays(app: AppHandle) -> Result<Vec<Display>, String> {
+    displays(&app)
+}
+
+/// The monitor the main window is on, if it's on one.
+#[tauri::command]
+pub fn current_display(app: AppHandle) -> Result<Option<Display>, String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+    Ok(window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|m| display(&m, primary.as_ref())))
+}
@@ -0,0 +1,85 @@
+// Native file drag-and-drop. The webview only exposes virtual File objects,
+// so we forward the real paths (with size and a mime guess, letting the
+// frontend reject oversized uploads before reading them) as `files-dropped`.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{DragDropEvent, Emitter, Manager, PhysicalPosition, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[derive(Clone, Serialize)]
+struct DroppedFile {
+    path: PathBuf,
+    size: u64,
+    mime: String,
+}
+
+#[derive(Clone, Serialize)]
+struct FilesDropped {
+    paths: Vec<DroppedFile>,
+    position: PhysicalPosition<f64>,
+}
+
+fn dropped_file(path: &Path) -> Option<DroppedFile> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    Some(DroppedFile {
+        path: path.to_path_buf(),
+        size: meta.len(),
+        mime: mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .essence_str()
+            .to_string(),
+    })
+}
+
+/// Resolve dropped paths to files. Directories are skipped, or expanded one
+/// level (files directly inside them) when `expand_dirs` is set.
+fn resolve(paths: &[PathBuf], expand_dirs: bool) -> Vec<DroppedFile> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            if !expand_dirs {
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(path) else {
+                continue;
+            };
+            let mut children: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+            children.sort();
+            files.extend(children.iter().filter_map(|p| dropped_file(p)));
+        } else if let Some(file) = dropped_file(path) {
+            files.push(file);
+        }
+    }
+    files
+}
+
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let w = window.clone();
+    window.on_window_event(move |event| {
+        let tauri::WindowEvent::DragDrop(DragDropEvent::Drop { paths, position }) = event else {
+            return;
+        };
+        let expand_dirs = w
+            .app_handle()
+            .state::<SettingsStore>()
+            .get()
+            .drop_expand_directories;
+        let files = resolve(paths, expand_dirs);
+        if files.is_empty() {
+            return;
+        }
+        let _ = w.emit(
+            "files-dropped",
+            FilesDropped {
+                paths: files,
+                position: *position,
+            },
+        );
+    });
+}
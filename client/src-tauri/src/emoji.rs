@@ -0,0 +1,83 @@
+// `nexus-emoji://` — emoji served lazily by shortcode instead of shipping
+// the whole set as web assets. `nexus-emoji://localhost/thumbs_up` (on
+// Windows `http://nexus-emoji.localhost/thumbs_up`) looks for
+// `thumbs_up.svg` then `thumbs_up.png` in a downloaded pack under
+// `<app data>/emoji/`, then in the pack bundled as a resource.
+//
+// Unknown shortcodes get a transparent placeholder rather than an error, so
+// the frontend never shows a broken image. The placeholder is only cached
+// briefly in case a pack that has it is downloaded later.
+
+use std::path::PathBuf;
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub const SCHEME: &str = "nexus-emoji";
+
+const PACK_DIR: &str = "emoji";
+const EXTENSIONS: &[(&str, &str)] = &[("svg", "image/svg+xml"), ("png", "image/png")];
+const FALLBACK: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1"/>"#;
+const CACHE_HIT: &str = "public, max-age=604800";
+const CACHE_MISS: &str = "public, max-age=300";
+
+/// The shortcode a request is for: the path without slashes, colons or an
+/// extension. `None` for anything that could step outside the pack.
+fn shortcode(request: &Request<Vec<u8>>) -> Option<String> {
+    let path = request.uri().path().trim_matches('/');
+    let name = path.trim_matches(':');
+    let name = EXTENSIONS
+        .iter()
+        .find_map(|(ext, _)| name.strip_suffix(&format!(".{ext}")))
+        .unwrap_or(name)
+        .to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'));
+    valid.then_some(name)
+}
+
+/// Pack directories in lookup order: downloaded first, then bundled.
+fn pack_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    [crate::paths::data_dir(app), app.path().resource_dir()]
+        .into_iter()
+        .filter_map(|dir| dir.ok().map(|d| d.join(PACK_DIR)))
+        .collect()
+}
+
+fn find<R: Runtime>(app: &AppHandle<R>, name: &str) -> Option<(Vec<u8>, &'static str)> {
+    pack_dirs(app).into_iter().find_map(|dir| {
+        EXTENSIONS.iter().find_map(|(ext, mime)| {
+            std::fs::read(dir.join(format!("{name}.{ext}")))
+                .ok()
+                .map(|body| (body, *mime))
+        })
+    })
+}
+
+fn handle<R: Runtime>(app: &AppHandle<R>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let found = shortcode(request).and_then(|name| find(app, &name));
+    let (body, content_type, cache) = match found {
+        Some((body, content_type)) => (body, content_type, CACHE_HIT),
+        None => (FALLBACK.to_vec(), "image/svg+xml", CACHE_MISS),
+    };
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CACHE_CONTROL, cache)
+        .body(body)
+        .unwrap_or_default()
+}
+
+/// Handler for `register_asynchronous_uri_scheme_protocol`.
+pub fn protocol<R: Runtime>(
+    ctx: tauri::UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: tauri::UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        responder.respond(handle(&app, &request));
+    });
+}
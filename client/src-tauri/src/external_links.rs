@@ -0,0 +1,86 @@
+// External links open in the system browser, never in the app's webview.
+// `open_external` is the entry point for the frontend; navigations and
+// new-window requests (`target="_blank"`, `window.open`) that leave the app
+// are intercepted and routed through the same checks, so a clicked link
+// can't replace the Nexus SPA.
+
+use tauri::webview::NewWindowResponse;
+use tauri::{AppHandle, Runtime, Url};
+use tauri_plugin_opener::OpenerExt;
+
+/// Schemes handed to the OS. Anything else (`file:`, `javascript:`, custom
+/// app schemes) is refused.
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Schemes the app itself is served from.
+const INTERNAL_SCHEMES: &[&str] = &[
+    "tauri",
+    "asset",
+    "ipc",
+    "about",
+    "data",
+    "blob",
+    crate::media_cache::SCHEME,
+    crate::emoji::SCHEME,
+    crate::load_errors::SCHEME,
+];
+
+fn is_internal<R: Runtime>(app: &AppHandle<R>, url: &Url) -> bool {
+    if INTERNAL_SCHEMES.contains(&url.scheme()) {
+        return true;
+    }
+    // Windows serves custom protocols from http(s)://<scheme>.localhost
+    if url.host_str().is_some_and(|h| h.ends_with(".localhost")) {
+        return true;
+    }
+    app.config()
+        .build
+        .dev_url
+        .as_ref()
+        .is_some_and(|dev| dev.origin() == url.origin())
+}
+
+fn open<R: Runtime>(app: &AppHandle<R>, url: &Url) -> Result<(), String> {
+    if !ALLOWED_SCHEMES.contains(&url.scheme()) {
+        return Err(format!("refusing to open {} link", url.scheme()));
+    }
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// `on_navigation` handler: lets in-app navigations through and sends
+/// everything else to the browser.
+pub fn on_navigation<R: Runtime>(app: &AppHandle<R>) -> impl Fn(&Url) -> bool + Send + 'static {
+    let app = app.clone();
+    move |url| {
+        if is_internal(&app, url) {
+            return true;
+        }
+        if let Err(e) = open(&app, url) {
+            log::warn!("blocked navigation to {url}: {e}");
+        }
+        false
+    }
+}
+
+/// `on_new_window` handler: new windows are never created in-app.
+pub fn on_new_window<R: Runtime>(
+    app: &AppHandle<R>,
+) -> impl Fn(Url, tauri::webview::NewWindowFeatures) -> NewWindowResponse<R> + Send + Sync + 'static {
+    let app = app.clone();
+    move |url, _features| {
+        if let Err(e) = open(&app, &url) {
+            log::warn!("blocked new window for {url}: {e}");
+        }
+        NewWindowResponse::Deny
+    }
+}
+
+/// Open `url` in the system browser (or mail client). Only http, https and
+/// mailto links are accepted.
+#[tauri::command]
+pub fn open_external(app: AppHandle, url: String) -> Result<(), String> {
+    let url = Url::parse(&url).map_err(|e| format!("invalid URL: {e}"))?;
+    open(&app, &url)
+}
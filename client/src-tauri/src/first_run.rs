@@ -0,0 +1,44 @@
+// First-run detection for onboarding. A marker file in the config dir
+// records that onboarding has been offered. It's created with `create_new`,
+// so when several calls race during startup exactly one of them sees the
+// first run.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+
+const MARKER_FILE: &str = ".onboarded";
+
+fn marker(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::paths::config_dir(app)
+        .map(|dir| dir.join(MARKER_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// True the first time it's called on an installation, false afterwards.
+#[tauri::command]
+pub fn is_first_run(app: AppHandle) -> Result<bool, String> {
+    let marker = marker(&app)?;
+    if let Some(dir) = marker.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    match std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&marker)
+    {
+        Ok(_) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Forget that onboarding was shown, so the next `is_first_run` is true.
+#[tauri::command]
+pub fn reset_first_run(app: AppHandle) -> Result<(), String> {
+    match std::fs::remove_file(marker(&app)?) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,62 @@
+// Frameless windows with an HTML titlebar. With `Settings.frameless` the
+// main and account windows are built without native decorations, and the
+// titlebar's buttons and drag region call the commands below.
+//
+// Resizing still works without decorations: the windowing layer keeps an
+// invisible resize border around undecorated resizable windows on all three
+// platforms, so the frontend doesn't need its own resize handles. It should
+// leave a few pixels at the window edges free of interactive elements so the
+// border stays reachable.
+//
+// Frontend wiring: `mousedown` (primary button) on the drag region calls
+// `start_window_drag`, `dblclick` on it calls `toggle_maximize`.
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+/// Switch between native and HTML titlebars for every main/account window.
+#[tauri::command]
+pub fn set_frameless(app: AppHandle, enabled: bool) -> Result<(), String> {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.frameless = enabled);
+    for (label, window) in app.webview_windows() {
+        if crate::accounts::is_account_window(&label) {
+            window
+                .set_decorations(!enabled)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Start moving the window with the mouse. Call from a `mousedown` handler.
+#[tauri::command]
+pub fn start_window_drag(window: WebviewWindow) -> Result<(), String> {
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn minimize_window(window: WebviewWindow) -> Result<(), String> {
+    window.minimize().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn maximize_window(window: WebviewWindow) -> Result<(), String> {
+    window.maximize().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn toggle_maximize(window: WebviewWindow) -> Result<(), String> {
+    if window.is_maximized().map_err(|e| e.to_string())? {
+        window.unmaximize().map_err(|e| e.to_string())
+    } else {
+        window.maximize().map_err(|e| e.to_string())
+    }
+}
+
+/// Same as the native close button, so close-to-tray still applies.
+#[tauri::command]
+pub fn close_window(window: WebviewWindow) -> Result<(), String> {
+    window.close().map_err(|e| e.to_string())
+}
@@ -0,0 +1,56 @@
+// Hardware acceleration toggle for the webview, for GPUs/drivers that
+// corrupt video. The switches involved are read once when the webview
+// process starts, so the setting only takes effect after a restart.
+//
+// - Linux: WEBKIT_DISABLE_COMPOSITING_MODE=1 turns off WebKitGTK's
+//   accelerated compositing.
+// - Windows: WebView2 is started with --disable-gpu.
+// - macOS: WKWebView doesn't offer a switch; the setting is a no-op.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::settings::SettingsStore;
+
+/// WebView2's default arguments (wry sets these when none are given) plus
+/// the GPU switch, since setting any arguments replaces the defaults.
+#[cfg(target_os = "windows")]
+pub const WEBVIEW2_DISABLE_GPU_ARGS: &str =
+    "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --disable-gpu";
+
+/// The setting the webview process was started with.
+static AT_LAUNCH: AtomicBool = AtomicBool::new(true);
+
+/// Apply the persisted setting. Must run at the very top of `run()`, before
+/// any webview is created.
+pub fn apply_early(enabled: bool) {
+    AT_LAUNCH.store(enabled, Ordering::Relaxed);
+    #[cfg(target_os = "linux")]
+    if !enabled {
+        std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+    }
+}
+
+/// Whether acceleration is on for this run. Webviews created later must
+/// match it, whatever the setting says now.
+pub fn at_launch() -> bool {
+    AT_LAUNCH.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HardwareAccelerationChanged {
+    pub requires_restart: bool,
+}
+
+#[tauri::command]
+pub fn set_hardware_acceleration(app: AppHandle, enabled: bool) -> HardwareAccelerationChanged {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.hardware_acceleration = enabled);
+    // Measured against this run rather than the previous setting, so
+    // toggling back before restarting needs no restart
+    HardwareAccelerationChanged {
+        requires_restart: !cfg!(target_os = "macos") && enabled != at_launch(),
+    }
+}
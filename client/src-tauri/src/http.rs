@@ -0,0 +1,24 @@
+// Shared HTTP client setup for requests made from Rust (the updater builds
+// its own). Honors the configured proxy.
+
+use tauri::{Manager, Runtime};
+
+pub fn client<R: Runtime, M: Manager<R>>(manager: &M) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().user_agent(concat!("Nexus/", env!("CARGO_PKG_VERSION")));
+    let proxy = crate::proxy::current(manager);
+    match proxy.mode {
+        crate::proxy::ProxyMode::None => builder = builder.no_proxy(),
+        // reqwest reads the system proxy env vars itself
+        crate::proxy::ProxyMode::System => {}
+        crate::proxy::ProxyMode::Manual => {
+            if let Some(url) = proxy.https_url() {
+                let mut p = reqwest::Proxy::all(url.as_str()).map_err(|e| e.to_string())?;
+                if let Some(no_proxy) = proxy.no_proxy.as_deref() {
+                    p = p.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+                }
+                builder = builder.proxy(p);
+            }
+        }
+    }
+    builder.build().map_err(|e| e.to_string())
+}
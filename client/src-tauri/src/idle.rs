@@ -0,0 +1,111 @@
+// OS idle detection for automatic "away" status. A background thread polls
+// the system idle time and emits `idle-state-changed` when the configured
+// threshold is crossed in either direction.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::settings::SettingsStore;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+struct IdleConfig {
+    enabled: bool,
+    threshold_secs: u64,
+}
+
+/// Shared between the commands and the polling thread. The condvar wakes the
+/// thread when the config changes, both to resume after being disabled and to
+/// apply a new threshold without waiting out the poll interval.
+pub struct IdleMonitor {
+    config: Mutex<IdleConfig>,
+    changed: Condvar,
+}
+
+#[derive(Clone, Serialize)]
+struct IdleStateChanged {
+    idle: bool,
+    seconds: u64,
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>, monitor: Arc<IdleMonitor>) {
+    let mut was_idle = false;
+    let mut config = monitor.config.lock().unwrap();
+    loop {
+        if !config.enabled {
+            if was_idle {
+                was_idle = false;
+                let _ = app.emit("idle-state-changed", IdleStateChanged { idle: false, seconds: 0 });
+            }
+            // Park until re-enabled; no polling while disabled
+            config = monitor.changed.wait_while(config, |c| !c.enabled).unwrap();
+        }
+
+        let threshold = config.threshold_secs;
+        drop(config);
+        match user_idle::UserIdle::get_time() {
+            Ok(idle) => {
+                let seconds = idle.as_seconds();
+                let is_idle = seconds >= threshold;
+                if is_idle != was_idle {
+                    was_idle = is_idle;
+                    let _ = app.emit("idle-state-changed", IdleStateChanged { idle: is_idle, seconds });
+                }
+            }
+            Err(e) => log::debug!("failed to read system idle time: {e}"),
+        }
+
+        config = monitor.config.lock().unwrap();
+        config = monitor.changed.wait_timeout(config, POLL_INTERVAL).unwrap().0;
+    }
+}
+
+/// Manage the monitor state, so the commands work before `start`.
+pub fn init(app: &tauri::App) {
+    let settings = app.state::<SettingsStore>().get();
+    let monitor = Arc::new(IdleMonitor {
+        config: Mutex::new(IdleConfig {
+            enabled: settings.idle_detection_enabled,
+            threshold_secs: settings.idle_threshold_secs,
+        }),
+        changed: Condvar::new(),
+    });
+    app.manage(monitor);
+}
+
+/// Start polling. Started by `deferred_init`.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let monitor = app.state::<Arc<IdleMonitor>>().inner().clone();
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("idle-monitor".into())
+        .spawn(move || poll_loop(handle, monitor))
+    {
+        log::warn!("failed to start idle monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn set_idle_threshold(app: AppHandle, seconds: u64) -> Result<(), String> {
+    if seconds == 0 {
+        return Err("idle threshold must be at least 1 second".into());
+    }
+    let monitor = app.state::<Arc<IdleMonitor>>();
+    monitor.config.lock().unwrap().threshold_secs = seconds;
+    monitor.changed.notify_all();
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.idle_threshold_secs = seconds);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_idle_detection_enabled(app: AppHandle, enabled: bool) {
+    let monitor = app.state::<Arc<IdleMonitor>>();
+    monitor.config.lock().unwrap().enabled = enabled;
+    monitor.changed.notify_all();
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.idle_detection_enabled = enabled);
+}
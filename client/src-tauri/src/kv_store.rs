@@ -0,0 +1,164 @@
+// A small persistent key-value store for frontend preferences that must
+// survive "Clear Cache" (which empties localStorage on some platforms).
+// Values are arbitrary JSON, kept in memory and written to `kv.json` in the
+// app data dir.
+//
+// Writes are batched: a change schedules a save `FLUSH_DELAY` later, and
+// further changes in that window ride along with it. `flush` writes any
+// outstanding changes right away and runs on exit.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+const KV_FILE: &str = "kv.json";
+const FLUSH_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct KvInner {
+    values: BTreeMap<String, Value>,
+    /// Changed since the last save
+    dirty: bool,
+    /// A delayed save is already on its way
+    flush_scheduled: bool,
+}
+
+pub struct KvStore {
+    path: Option<PathBuf>,
+    inner: Mutex<KvInner>,
+    /// Held while saving, so an older snapshot can't overwrite a newer one
+    saving: Mutex<()>,
+}
+
+impl KvStore {
+    /// Read the store from disk; a missing or corrupt file starts it empty.
+    /// A corrupt one is first renamed to `kv.json.corrupt`, so the next save
+    /// doesn't destroy what's left in it.
+    pub fn load<R: Runtime, M: Manager<R>>(manager: &M) -> Self {
+        let path = crate::paths::data_dir(manager).ok().map(|d| d.join(KV_FILE));
+        let values = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok().map(|raw| (p, raw)))
+            .and_then(|(p, raw)| match serde_json::from_str(&raw) {
+                Ok(values) => Some(values),
+                Err(e) => {
+                    log::warn!("kv store file is corrupt, starting empty: {e}");
+                    if let Err(e) = std::fs::rename(p, p.with_extension("json.corrupt")) {
+                        log::warn!("failed to set aside corrupt kv store: {e}");
+                    }
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            inner: Mutex::new(KvInner {
+                values,
+                ..Default::default()
+            }),
+            saving: Mutex::new(()),
+        }
+    }
+
+    fn write(&self, values: &BTreeMap<String, Value>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        match serde_json::to_string(values) {
+            Ok(json) => {
+                // Write-then-rename so a crash mid-write can't truncate the file
+                let tmp = path.with_extension("json.tmp");
+                let result = std::fs::write(&tmp, json).and_then(|()| std::fs::rename(&tmp, path));
+                if let Err(e) = result {
+                    log::warn!("failed to save the kv store: {e}");
+                }
+            }
+            Err(e) => log::warn!("failed to serialize the kv store: {e}"),
+        }
+    }
+
+    /// Apply `f` and schedule a save if it changed anything.
+    fn change<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        f: impl FnOnce(&mut BTreeMap<String, Value>) -> bool,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        if !f(&mut inner.values) {
+            return;
+        }
+        inner.dirty = true;
+        if inner.flush_scheduled {
+            return;
+        }
+        inner.flush_scheduled = true;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(FLUSH_DELAY).await;
+            let _ = tauri::async_runtime::spawn_blocking(move || flush(&app)).await;
+        });
+    }
+}
+
+/// Write outstanding changes now.
+pub fn flush<R: Runtime>(app: &AppHandle<R>) {
+    let Some(store) = app.try_state::<KvStore>() else {
+        return;
+    };
+    let _saving = store.saving.lock().unwrap();
+    let snapshot = {
+        let mut inner = store.inner.lock().unwrap();
+        inner.flush_scheduled = false;
+        if !inner.dirty {
+            return;
+        }
+        inner.dirty = false;
+        inner.values.clone()
+    };
+    store.write(&snapshot);
+}
+
+#[tauri::command]
+pub fn kv_get(app: AppHandle, key: String) -> Option<Value> {
+    app.state::<KvStore>().inner.lock().unwrap().values.get(&key).cloned()
+}
+
+#[tauri::command]
+pub fn kv_set(app: AppHandle, key: String, value: Value) {
+    app.state::<KvStore>().change(&app, |values| {
+        values.insert(key, value.clone()) != Some(value)
+    });
+}
+
+/// Returns whether the key existed.
+#[tauri::command]
+pub fn kv_delete(app: AppHandle, key: String) -> bool {
+    let mut existed = false;
+    app.state::<KvStore>().change(&app, |values| {
+        existed = values.remove(&key).is_some();
+        existed
+    });
+    existed
+}
+
+/// Keys starting with `prefix` (all keys when it's omitted), sorted.
+#[tauri::command]
+pub fn kv_keys(app: AppHandle, prefix: Option<String>) -> Vec<String> {
+    let store = app.state::<KvStore>();
+    let inner = store.inner.lock().unwrap();
+    let prefix = prefix.unwrap_or_default();
+    inner
+        .values
+        .range(prefix.clone()..)
+        .map(|(key, _)| key)
+        .take_while(|key| key.starts_with(&prefix))
+        .cloned()
+        .collect()
+}
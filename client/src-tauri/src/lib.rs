@@ -1,11 +1,370 @@
-use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use tauri::menu::{AboutMetadata, CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
-use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::utils::config::BackgroundThrottlingPolicy;
 
+mod accessibility;
+mod accounts;
+mod activity;
+mod always_on_top;
+mod app_info;
+mod attention;
+mod attachments;
+mod audio;
+mod autostart;
+mod badge;
+mod cache;
+mod capture;
+mod clipboard;
+mod close_to_tray;
+mod context_menu;
+mod crash;
+mod custom_css;
+mod deep_link;
+mod deferred_init;
+mod devtools;
+mod diagnostics;
+mod disk_space;
+mod displays;
+mod drag_drop;
+mod emoji;
+mod external_links;
+mod first_run;
+mod frameless;
+mod hardware_acceleration;
+mod http;
+mod idle;
+mod kv_store;
+mod lifecycle;
+mod load_errors;
+mod locale;
+mod logging;
+mod media_cache;
+mod media_keys;
+mod menu_visibility;
+mod mic_monitor;
+mod mic_mute;
+mod network;
+mod notifications;
+mod opacity;
+mod paths;
+mod permissions;
+mod pip;
+mod power;
+mod process_stats;
+mod protocol_handler;
+mod proxy;
+mod ptt;
+mod reload;
+mod reminders;
+mod safe_mode;
+mod secrets;
+mod settings;
+mod shortcuts;
+mod sleep_inhibit;
+mod spellcheck;
+mod startup;
+mod startup_timing;
+mod suspend;
+mod test_tone;
+mod theme;
+mod title;
+mod tray;
+mod updater;
+mod user_agent;
+mod video_fullscreen;
+mod window_effect;
+mod window_events;
+mod window_icon;
+mod window_loaded;
+mod window_size;
+mod window_state;
+mod zoom;
+
+/// Bring the most recently focused account window (normally the main
+/// window) to the front, restoring it from the tray if hidden.
+pub(crate) fn show_main_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    if let Some(w) = accounts::last_focused(app) {
+        let _ = w.unminimize();
+        let _ = w.show();
+        let _ = w.set_focus();
+    }
+    window_events::refresh(app);
+}
+
+/// Dock icon click on macOS: bring back a window hidden to the tray, or
+/// build the main window again from its saved geometry if every account
+/// window is gone.
+#[cfg(target_os = "macos")]
+fn reopen(app: &tauri::AppHandle) {
+    if accounts::last_focused(app).is_some() {
+        show_main_window(app);
+        return;
+    }
+    match create_main_window(app) {
+        Ok(window) => {
+            let _ = window.show();
+            let _ = window.set_focus();
+            window_events::refresh(app);
+        }
+        Err(e) => log::warn!("failed to recreate the main window: {e}"),
+    }
+}
+
+/// The window an app-menu action should apply to: whichever one has focus,
+/// falling back to `default` (the main window).
+fn focused_window<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    default: tauri::WebviewWindow<R>,
+) -> tauri::WebviewWindow<R> {
+    app.webview_windows()
+        .into_values()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .unwrap_or(default)
+}
+
+/// Set up the webview permissions voice and video need (camera,
+/// microphone, screen capture). Applies to every window that loads the app.
+pub(crate) fn grant_webview_permissions(window: &tauri::WebviewWindow) -> tauri::Result<()> {
+    // Enable media on Linux (WebKit2GTK); requests go through the saved
+    // permission policies
+    #[cfg(target_os = "linux")]
+    {
+        window.with_webview(|webview| {
+            use webkit2gtk::{SettingsExt, WebViewExt};
+
+            let wv = webview.inner();
+
+            if let Some(settings) = WebViewExt::settings(&wv) {
+                settings.set_enable_media_stream(true);
+                settings.set_enable_mediasource(true);
+                settings.set_media_playback_requires_user_gesture(false);
+            }
+        })?;
+        permissions::init(window)?;
+    }
+
+    // Auto-grant all permissions on Windows (WebView2)
+    #[cfg(target_os = "windows")]
+    {
+        window.with_webview(|webview| {
+            unsafe {
+                use webview2_com::Microsoft::Web::WebView2::Win32::*;
+                use webview2_com::PermissionRequestedEventHandler;
+
+                let core = webview.controller().CoreWebView2().unwrap();
+                let handler = PermissionRequestedEventHandler::create(
+                    Box::new(|_sender, args| {
+                        if let Some(args) = args {
+                            args.SetState(
+                                COREWEBVIEW2_PERMISSION_STATE_ALLOW,
+                            )?;
+                        }
+                        Ok(())
+                    }),
+                );
+                let mut token = std::mem::zeroed();
+                core.add_PermissionRequested(&handler, &mut token)
+                    .unwrap();
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// What sets one app window apart from another: the main window or a
+/// per-account one.
+pub(crate) struct AppWindowOptions<'a> {
+    /// The account of a per-account window, `None` for main. It's exposed
+    /// to the frontend and keys the window's own webview storage.
+    pub account_id: Option<&'a str>,
+    /// The proxy the webview starts with
+    pub proxy: &'a proxy::ProxyConfig,
+    pub always_on_top: bool,
+    pub transparent: bool,
+}
+
+type WindowBuilder<'a> = tauri::WebviewWindowBuilder<'a, tauri::Wry, tauri::AppHandle>;
+
+/// The options a webview is started with that every window has to share:
+/// WebView2 webviews on one user data folder must agree on them, or all but
+/// the first fail to be created. Windows of `account_id` get storage of
+/// their own. Call `user_agent::apply` on Linux once the window is built.
+pub(crate) fn with_webview_environment<'a>(
+    app: &tauri::AppHandle,
+    builder: WindowBuilder<'a>,
+    account_id: Option<&str>,
+    proxy: &proxy::ProxyConfig,
+) -> tauri::Result<WindowBuilder<'a>> {
+    #[cfg(not(target_os = "linux"))]
+    let builder = builder.user_agent(&user_agent::get());
+    #[cfg(target_os = "windows")]
+    let builder = if hardware_acceleration::at_launch() {
+        builder
+    } else {
+        builder.additional_browser_args(hardware_acceleration::WEBVIEW2_DISABLE_GPU_ARGS)
+    };
+    // Portable installs keep the shared storage in the data dir too
+    #[cfg(target_os = "macos")]
+    let builder = match account_id {
+        Some(id) => builder.data_store_identifier(accounts::data_store_identifier(id)),
+        None => builder,
+    };
+    #[cfg(not(target_os = "macos"))]
+    let builder = match account_id {
+        Some(id) => builder.data_directory(accounts::data_directory(app, id)?),
+        None if paths::overridden().is_some() => {
+            builder.data_directory(paths::local_data_dir(app)?)
+        }
+        None => builder,
+    };
+    // WebView2 only accepts a proxy at creation time
+    #[cfg(target_os = "windows")]
+    let builder = match proxy.https_url() {
+        Some(url) => builder.proxy_url(url),
+        None => builder,
+    };
+    #[cfg(target_os = "macos")]
+    let _ = app;
+    #[cfg(not(target_os = "windows"))]
+    let _ = proxy;
+    Ok(builder)
+}
+
+/// Build an app window and hook up everything that tracks it. It's created
+/// hidden; the caller shows it.
+///
+/// Built in code rather than from the config so we can set
+/// BackgroundThrottlingPolicy::Disabled — prevents macOS from suspending the
+/// WebView's JS timers & WebSocket when backgrounded.
+pub(crate) fn build_app_window(
+    app: &tauri::AppHandle,
+    label: &str,
+    url: tauri::WebviewUrl,
+    options: AppWindowOptions<'_>,
+) -> tauri::Result<tauri::WebviewWindow> {
+    let builder = tauri::WebviewWindowBuilder::new(app, label, url)
+        .title("Nexus")
+        .inner_size(1280.0, 800.0)
+        .min_inner_size(window_size::DEFAULT_MIN_SIZE.0, window_size::DEFAULT_MIN_SIZE.1)
+        .resizable(true)
+        .fullscreen(false)
+        .decorations(!app.state::<settings::SettingsStore>().get().frameless)
+        .always_on_top(options.always_on_top)
+        .theme(theme::forced(app))
+        .transparent(options.transparent)
+        .devtools(devtools::enabled())
+        .visible(false)
+        .background_throttling(BackgroundThrottlingPolicy::Disabled);
+    let builder = match options.account_id {
+        Some(id) => builder.initialization_script(format!(
+            "window.__NEXUS_ACCOUNT_ID = {};",
+            serde_json::to_string(id)?
+        )),
+        None => builder,
+    };
+    let builder = builder
+        .initialization_script(zoom::INIT_SCRIPT)
+        .on_navigation(external_links::on_navigation(app))
+        .on_new_window(external_links::on_new_window(app));
+    let builder = if safe_mode::active() {
+        builder.initialization_script(safe_mode::INIT_SCRIPT)
+    } else {
+        builder
+    };
+    #[cfg(target_os = "linux")]
+    let builder = builder.initialization_script(theme::INIT_SCRIPT);
+    #[cfg(target_os = "macos")]
+    let builder = builder.initialization_script(video_fullscreen::INIT_SCRIPT);
+    let builder = with_webview_environment(app, builder, options.account_id, options.proxy)?;
+    // Announce leftover crash reports once the main window has loaded
+    let builder = builder.on_page_load(|webview, payload| {
+        load_errors::on_page_load(&webview, &payload);
+        window_loaded::on_page_load(&webview, &payload);
+        if webview.label() == "main" && payload.event() == tauri::webview::PageLoadEvent::Finished {
+            static ANNOUNCED: std::sync::Once = std::sync::Once::new();
+            ANNOUNCED.call_once(|| {
+                startup_timing::mark("main window loaded");
+                crash::announce_pending(webview.app_handle());
+                safe_mode::announce(webview.app_handle());
+                deferred_init::run(webview.app_handle());
+            });
+        }
+    });
+    let window = builder.build()?;
+    #[cfg(target_os = "linux")]
+    user_agent::apply(&window);
+
+    // Restore saved geometry before the window is first shown so it
+    // doesn't visibly jump from the default position. With nothing
+    // saved, size the main window to the monitor the user is looking at.
+    if !window_state::restore(&window) && options.account_id.is_none() {
+        window_state::apply_default(&window, 0.7, window_size::DEFAULT_MIN_SIZE);
+    }
+    window_state::track(&window);
+    zoom::init(&window);
+    attention::clear_on_focus(&window);
+    media_keys::track(&window);
+    window_events::track(&window);
+    theme::init(&window);
+    // The native drag-drop handler is on by default for windows built
+    // in code (the equivalent of dragDropEnabled: true)
+    drag_drop::init(&window);
+    spellcheck::init(&window);
+    context_menu::init(&window)?;
+    video_fullscreen::init(&window)?;
+    custom_css::init(&window);
+    load_errors::init(&window)?;
+    proxy::apply_to_webview(&window, options.proxy);
+    grant_webview_permissions(&window)?;
+
+    // Set the window icon so macOS screen sharing picker shows the Nexus icon
+    // instead of the default Tauri icon
+    let icon = window_icon::default_icon()?;
+    window.set_icon(icon)?;
+
+    // Window close → hide to tray (or quit), once no other account window
+    // is left open
+    accounts::track(&window);
+    Ok(window)
+}
+
+/// Build the main window. It's created hidden; the caller shows it.
+fn create_main_window(app: &tauri::AppHandle) -> tauri::Result<tauri::WebviewWindow> {
+    let main_window = build_app_window(
+        app,
+        "main",
+        tauri::WebviewUrl::App("index.html".into()),
+        AppWindowOptions {
+            account_id: None,
+            proxy: &proxy::at_launch(app),
+            always_on_top: app.state::<settings::SettingsStore>().get().always_on_top,
+            transparent: window_effect::wants_transparency(app),
+        },
+    )?;
+    opacity::init(&main_window);
+    window_effect::init(&main_window);
+    Ok(main_window)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    startup_timing::begin();
+    let args: Vec<String> = std::env::args().collect();
+    // First, so everything below (crash reports included) uses the right dirs
+    let data_dir_warning = paths::init(&args);
+    safe_mode::init_early(&args);
+    let context = tauri::generate_context!();
+    crash::install_panic_hook(
+        &context.config().identifier,
+        context.package_info().version.to_string(),
+    );
+
+    // Settings that have to be in place before the webview starts
+    let early_settings = settings::load_early(&context.config().identifier);
+    hardware_acceleration::apply_early(early_settings.hardware_acceleration);
+    let initial_route = deep_link::route_from_args(&args);
+    let startup_mode = startup::StartupMode::from_args(args, &early_settings);
+
     // Disable DMA-BUF renderer in WebKitGTK — the default renderer causes
     // gray/blank screens on many Linux systems (especially NVIDIA GPUs).
     // See: https://github.com/tauri-apps/tauri/issues/9304
@@ -13,179 +372,246 @@ pub fn run() {
     std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
 
     tauri::Builder::default()
+        // Must be registered first so a second launch exits before any other
+        // plugin or window is initialized
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            show_main_window(app);
+            for url in argv.into_iter().filter(|a| deep_link::is_deep_link(a)) {
+                deep_link::deliver(app, url);
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![startup::AUTOSTART_ARG]),
+        ))
+        .plugin(logging::plugin())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .setup(|app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    ptt::handle_shortcut(app, shortcut, event);
+                    media_keys::handle_shortcut(app, shortcut, event);
+                })
+                .build(),
+        )
+        .register_asynchronous_uri_scheme_protocol(media_cache::SCHEME, media_cache::protocol)
+        .register_asynchronous_uri_scheme_protocol(emoji::SCHEME, emoji::protocol)
+        .register_uri_scheme_protocol(load_errors::SCHEME, load_errors::protocol)
+        .invoke_handler(tauri::generate_handler![
+            accessibility::get_accessibility_prefs,
+            accounts::get_account_proxy,
+            accounts::list_account_windows,
+            accounts::open_account_window,
+            accounts::set_account_proxy,
+            activity::clear_activity,
+            activity::get_activity,
+            activity::reapply_activity,
+            activity::set_activity,
+            always_on_top::set_always_on_top,
+            app_info::get_app_info,
+            attention::request_user_attention,
+            attachments::save_attachment,
+            attachments::save_attachment_from_url,
+            audio::list_audio_devices,
+            audio::set_output_device,
+            autostart::disable_autostart,
+            autostart::enable_autostart,
+            autostart::is_autostart_enabled,
+            badge::set_badge_count,
+            badge::set_taskbar_overlay,
+            cache::clear_cache,
+            cache::get_cache_size,
+            capture::capture_window_screenshot,
+            capture::list_capture_sources,
+            clipboard::read_clipboard_image,
+            clipboard::read_clipboard_text,
+            close_to_tray::set_close_to_tray,
+            context_menu::show_context_menu,
+            crash::get_pending_crash_reports,
+            crash::submit_crash_report,
+            custom_css::clear_injected_css,
+            custom_css::inject_css,
+            deep_link::deep_link_ready,
+            devtools::devtools_available,
+            diagnostics::get_diagnostics,
+            disk_space::get_disk_space,
+            displays::current_display,
+            displays::list_displays,
+            external_links::open_external,
+            first_run::is_first_run,
+            first_run::reset_first_run,
+            frameless::close_window,
+            frameless::maximize_window,
+            frameless::minimize_window,
+            frameless::set_frameless,
+            frameless::start_window_drag,
+            frameless::toggle_maximize,
+            hardware_acceleration::set_hardware_acceleration,
+            idle::set_idle_detection_enabled,
+            idle::set_idle_threshold,
+            kv_store::kv_delete,
+            kv_store::kv_get,
+            kv_store::kv_keys,
+            kv_store::kv_set,
+            lifecycle::cancel_quit,
+            lifecycle::confirm_quit,
+            lifecycle::ready_to_quit,
+            lifecycle::restart_app,
+            lifecycle::restart_ready,
+            lifecycle::set_call_active,
+            load_errors::reload,
+            locale::get_locale,
+            locale::get_preferred_languages,
+            logging::export_logs,
+            logging::set_log_level,
+            media_keys::set_media_controls_enabled,
+            media_keys::set_media_keys_in_background,
+            menu_visibility::is_menu_visible,
+            menu_visibility::set_menu_visible,
+            mic_monitor::start_mic_monitor,
+            mic_monitor::stop_mic_monitor,
+            mic_mute::is_mic_muted,
+            mic_mute::set_mic_muted,
+            network::check_network_now,
+            network::ping_host,
+            network::set_server_url,
+            notifications::notification_permission_state,
+            notifications::open_notification_settings,
+            notifications::request_notification_permission,
+            notifications::show_message_notification,
+            notifications::show_progress_notification,
+            opacity::set_window_opacity,
+            permissions::get_permission_policies,
+            permissions::respond_permission,
+            permissions::set_permission_policy,
+            pip::close_pip_window,
+            pip::get_pip_stream,
+            pip::open_pip_window,
+            pip::set_pip_stream,
+            power::get_power_status,
+            process_stats::get_process_stats,
+            protocol_handler::is_default_protocol_handler,
+            protocol_handler::set_as_default_protocol_handler,
+            proxy::get_proxy,
+            proxy::set_proxy,
+            ptt::set_ptt_shortcut,
+            reminders::cancel_scheduled_notification,
+            reminders::schedule_notification,
+            safe_mode::is_safe_mode,
+            safe_mode::relaunch_safe_mode,
+            secrets::delete_secret,
+            secrets::get_secret,
+            secrets::store_secret,
+            settings::get_settings,
+            settings::update_settings,
+            shortcuts::get_shortcuts,
+            shortcuts::reset_shortcuts,
+            shortcuts::set_shortcut,
+            sleep_inhibit::inhibit_sleep,
+            sleep_inhibit::uninhibit_sleep,
+            spellcheck::get_available_spellcheck_languages,
+            spellcheck::set_spellcheck_languages,
+            startup::set_start_minimized,
+            test_tone::play_test_tone,
+            test_tone::stop_test_tone,
+            theme::get_system_theme,
+            theme::get_window_theme,
+            theme::report_system_theme,
+            theme::set_window_theme,
+            title::set_unread_in_title,
+            title::set_window_title,
+            tray::set_tray_click_behavior,
+            tray::set_tray_menu,
+            tray::set_tray_presence,
+            tray::set_tray_tooltip,
+            tray::set_tray_unread,
+            updater::check_for_update,
+            updater::download_and_stage_update,
+            updater::get_update_channel,
+            updater::install_staged_update,
+            updater::set_update_channel,
+            user_agent::get_user_agent,
+            video_fullscreen::report_video_fullscreen,
+            window_effect::set_window_effect,
+            window_icon::reset_window_icon,
+            window_icon::set_window_icon,
+            window_loaded::is_window_loaded,
+            window_size::set_window_size_constraints,
+            zoom::get_zoom,
+            zoom::set_zoom,
+            zoom::zoom_by_steps,
+        ])
+        .setup(move |app| {
+            if let Some(warning) = &data_dir_warning {
+                log::warn!("{warning}");
             }
+            crash::init(app);
+            safe_mode::init(app);
+            app.manage(settings::SettingsStore::new(settings::load(app.handle())));
+            app.manage(kv_store::KvStore::load(app.handle()));
+            logging::init(app);
+            app.manage(lifecycle::LifecycleState::default());
+            app.manage(notifications::ProgressNotifications::default());
+            app.manage(updater::UpdaterState::default());
+            app.manage(context_menu::ContextMenuState::default());
+            app.manage(pip::PipState::default());
+            app.manage(accounts::AccountWindows::default());
+            app.manage(mic_monitor::MicMonitorState::default());
+            app.manage(test_tone::TestToneState::default());
+            app.manage(activity::ActivityState::default());
+            app.manage(title::TitleState::default());
+            app.manage(sleep_inhibit::SleepInhibitState::default());
+            app.manage(video_fullscreen::VideoFullscreenState::default());
+            app.manage(load_errors::LoadWatchState::default());
+            app.manage(window_loaded::LoadedWindows::default());
+            app.manage(window_events::WindowVisibility::default());
+            #[cfg(target_os = "windows")]
+            app.manage(custom_css::InjectedCssScripts::default());
 
-            // Create main window programmatically so we can set
-            // BackgroundThrottlingPolicy::Disabled — prevents macOS from
-            // suspending the WebView's JS timers & WebSocket when backgrounded.
-            let main_window = tauri::WebviewWindowBuilder::new(
-                app,
-                "main",
-                tauri::WebviewUrl::App("index.html".into()),
-            )
-                .title("Nexus")
-                .inner_size(1280.0, 800.0)
-                .min_inner_size(940.0, 560.0)
-                .resizable(true)
-                .fullscreen(false)
-                .decorations(true)
-                .background_throttling(BackgroundThrottlingPolicy::Disabled)
-                .build()?;
+            let main_window = create_main_window(app.handle())?;
+            startup::show(&main_window, startup_mode)?;
+            startup_timing::mark("main window shown");
+            window_events::refresh(app.handle());
 
-            // Auto-grant media permissions on Linux (WebKit2GTK)
-            #[cfg(target_os = "linux")]
-            {
-                main_window.with_webview(|webview| {
-                    use webkit2gtk::{PermissionRequestExt, SettingsExt, WebViewExt};
+            // ── Tray icon ───────────────────────────────────────────
+            tray::init(app)?;
 
-                    let wv = webview.inner();
+            // ── Push-to-talk ────────────────────────────────────────
+            ptt::init(app);
 
-                    if let Some(settings) = WebViewExt::settings(&wv) {
-                        settings.set_enable_media_stream(true);
-                        settings.set_enable_mediasource(true);
-                        settings.set_media_playback_requires_user_gesture(false);
-                    }
+            // ── Media keys ──────────────────────────────────────────
+            media_keys::init(app);
 
-                    wv.connect_permission_request(|_wv, request| {
-                        PermissionRequestExt::allow(request);
-                        true
-                    });
-                })?;
-            }
+            // ── System sleep/wake ───────────────────────────────────
+            suspend::init(app);
 
-            // Auto-grant all permissions on Windows (WebView2)
-            #[cfg(target_os = "windows")]
-            {
-                main_window.with_webview(|webview| {
-                    unsafe {
-                        use webview2_com::Microsoft::Web::WebView2::Win32::*;
-                        use webview2_com::PermissionRequestedEventHandler;
-
-                        let core = webview.controller().CoreWebView2().unwrap();
-                        let handler = PermissionRequestedEventHandler::create(
-                            Box::new(|_sender, args| {
-                                if let Some(args) = args {
-                                    args.SetState(
-                                        COREWEBVIEW2_PERMISSION_STATE_ALLOW,
-                                    )?;
-                                }
-                                Ok(())
-                            }),
-                        );
-                        let mut token = std::mem::zeroed();
-                        core.add_PermissionRequested(&handler, &mut token)
-                            .unwrap();
-                    }
-                })?;
-            }
+            // ── Reminders ───────────────────────────────────────────
+            reminders::init(app);
 
-            // ── Window close → hide to tray (or quit) ─────────────
-            // Read saved preference from app data dir
-            let minimize_to_tray_initial = app.path().app_data_dir()
-                .ok()
-                .and_then(|d| std::fs::read_to_string(d.join("minimize_to_tray.txt")).ok())
-                .map(|s| s.trim() != "false")
-                .unwrap_or(true);
-            let minimize_to_tray = Arc::new(Mutex::new(minimize_to_tray_initial));
-
-            // Set the window icon so macOS screen sharing picker shows the Nexus icon
-            // instead of the default Tauri icon
-            let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/icon.png"))?;
-            main_window.set_icon(icon)?;
-
-            #[cfg(not(target_os = "macos"))]
-            let window_for_close = main_window.clone();
-            let app_handle_for_close = app.handle().clone();
-            let minimize_for_close = minimize_to_tray.clone();
-            main_window.on_window_event(move |event| {
-                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                    if *minimize_for_close.lock().unwrap() {
-                        api.prevent_close();
-                        // On macOS, hide the app (not just the window) so clicking
-                        // the dock icon automatically restores it via the native
-                        // applicationShouldHandleReopen delegate
-                        #[cfg(target_os = "macos")]
-                        {
-                            use objc2_app_kit::NSApplication;
-                            use objc2::MainThreadMarker;
-                            let mtm = unsafe { MainThreadMarker::new_unchecked() };
-                            let ns_app = NSApplication::sharedApplication(mtm);
-                            ns_app.hide(None);
-                        }
-                        #[cfg(not(target_os = "macos"))]
-                        let _ = window_for_close.hide();
-                    } else {
-                        app_handle_for_close.exit(0);
-                    }
-                }
-            });
+            // ── Deep links (nexus://) ───────────────────────────────
+            deep_link::init(app, initial_route)?;
 
-            // ── Tray icon ───────────────────────────────────────────
-            let show_item = MenuItemBuilder::new("Show")
-                .id("tray_show")
-                .build(app)?;
-            let quit_item = MenuItemBuilder::new("Quit")
-                .id("tray_quit")
-                .build(app)?;
-            let tray_menu = MenuBuilder::new(app)
-                .items(&[&show_item, &quit_item])
-                .build()?;
+            // ── Idle detection (auto-away) ──────────────────────────
+            idle::init(app);
 
-            let app_handle = app.handle().clone();
-            TrayIconBuilder::new()
-                .icon(tauri::image::Image::from_bytes(include_bytes!("../icons/tray-icon@2x.png"))?)
-                .icon_as_template(true)
-                .tooltip("Nexus")
-                .menu(&tray_menu)
-                .on_menu_event(move |_app, event| {
-                    let id = event.id().as_ref();
-                    match id {
-                        "tray_show" => {
-                            if let Some(w) = app_handle.get_webview_window("main") {
-                                let _ = w.show();
-                                let _ = w.set_focus();
-                            }
-                        }
-                        "tray_quit" => {
-                            app_handle.exit(0);
-                        }
-                        _ => {}
-                    }
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(w) = app.get_webview_window("main") {
-                            let _ = w.show();
-                            let _ = w.set_focus();
-                        }
-                    }
-                })
-                .build(app)?;
+            // ── Connection health ───────────────────────────────────
+            network::init(app);
+
+            // ── Background monitors and update check ────────────────
+            // Started after the first paint; see deferred_init
+            deferred_init::schedule_fallback(app.handle());
 
             // ── Build Application Menu ──────────────────────────────
 
             // File menu
             let settings_item = MenuItemBuilder::new("Settings")
                 .id("open_settings")
-                .accelerator("CmdOrCtrl+,")
+                .accelerator(shortcuts::accelerator(app, "open_settings"))
                 .build(app)?;
 
             let change_server_item = MenuItemBuilder::new("Change Server...")
@@ -194,12 +620,13 @@ pub fn run() {
 
             let minimize_to_tray_item = CheckMenuItemBuilder::new("Minimize to Tray")
                 .id("toggle_minimize_to_tray")
-                .checked(minimize_to_tray_initial)
+                .checked(close_to_tray::is_enabled(app.handle()))
                 .build(app)?;
+            app.manage(close_to_tray::CloseToTrayMenuItem(minimize_to_tray_item.clone()));
 
             let quit_menu_item = MenuItemBuilder::new("Quit")
                 .id("quit_app")
-                .accelerator("CmdOrCtrl+Q")
+                .accelerator(shortcuts::accelerator(app, "quit_app"))
                 .build(app)?;
 
             let file_menu = SubmenuBuilder::new(app, "File")
@@ -226,52 +653,93 @@ pub fn run() {
             // View menu — custom items handled in on_menu_event
             let reload_item = MenuItemBuilder::new("Reload")
                 .id("reload")
-                .accelerator("CmdOrCtrl+R")
+                .accelerator(shortcuts::accelerator(app, "reload"))
+                .build(app)?;
+
+            let hard_reload_item = MenuItemBuilder::new("Hard Reload")
+                .id("hard_reload")
+                .accelerator(shortcuts::accelerator(app, "hard_reload"))
                 .build(app)?;
 
             let devtools_item = MenuItemBuilder::new("Toggle Developer Tools")
                 .id("toggle_devtools")
-                .accelerator("CmdOrCtrl+Shift+I")
+                .accelerator(shortcuts::accelerator(app, "toggle_devtools"))
                 .build(app)?;
 
             let zoom_in_item = MenuItemBuilder::new("Zoom In")
                 .id("zoom_in")
-                .accelerator("CmdOrCtrl+=")
+                .accelerator(shortcuts::accelerator(app, "zoom_in"))
                 .build(app)?;
 
             let zoom_out_item = MenuItemBuilder::new("Zoom Out")
                 .id("zoom_out")
-                .accelerator("CmdOrCtrl+-")
+                .accelerator(shortcuts::accelerator(app, "zoom_out"))
                 .build(app)?;
 
             let zoom_reset_item = MenuItemBuilder::new("Reset Zoom")
                 .id("zoom_reset")
-                .accelerator("CmdOrCtrl+0")
+                .accelerator(shortcuts::accelerator(app, "zoom_reset"))
                 .build(app)?;
 
             let fullscreen_item = MenuItemBuilder::new("Toggle Fullscreen")
                 .id("toggle_fullscreen")
-                .accelerator("F11")
+                .accelerator(shortcuts::accelerator(app, "toggle_fullscreen"))
                 .build(app)?;
 
+            let always_on_top_item = CheckMenuItemBuilder::new("Always on Top")
+                .id("toggle_always_on_top")
+                .checked(app.state::<settings::SettingsStore>().get().always_on_top)
+                .build(app)?;
+            app.manage(always_on_top::AlwaysOnTopMenuItem(always_on_top_item.clone()));
+
+            // Leave the devtools entry out rather than showing a dead item
             let view_menu = SubmenuBuilder::new(app, "View")
                 .item(&reload_item)
-                .item(&devtools_item)
+                .item(&hard_reload_item);
+            let view_menu = if devtools::enabled() {
+                view_menu.item(&devtools_item)
+            } else {
+                view_menu
+            };
+            let view_menu = view_menu
                 .separator()
                 .item(&zoom_in_item)
                 .item(&zoom_out_item)
                 .item(&zoom_reset_item)
                 .separator()
                 .item(&fullscreen_item)
+                .item(&always_on_top_item)
+                .build()?;
+
+            // Settings menu
+            let autostart_item = CheckMenuItemBuilder::new("Start Nexus at Login")
+                .id("toggle_autostart")
+                .checked(autostart::is_enabled(app.handle()))
+                .build(app)?;
+            app.manage(autostart::AutostartMenuItem(autostart_item.clone()));
+
+            let settings_menu = SubmenuBuilder::new(app, "Settings")
+                .item(&autostart_item)
                 .build()?;
 
             // Help menu
             let check_updates_item = MenuItemBuilder::new("Check for Updates...")
                 .id("check_updates")
                 .build(app)?;
+            app.manage(updater::CheckUpdatesMenuItem(check_updates_item.clone()));
+
+            let export_logs_item = MenuItemBuilder::new("Export Logs…")
+                .id("export_logs")
+                .build(app)?;
+
+            let copy_diagnostics_item = MenuItemBuilder::new("Copy Diagnostics")
+                .id("copy_diagnostics")
+                .build(app)?;
 
             let help_menu = SubmenuBuilder::new(app, "Help")
                 .item(&check_updates_item)
+                .item(&export_logs_item)
+                .item(&copy_diagnostics_item)
                 .separator()
                 .about(Some(AboutMetadata {
                     name: Some("Nexus".into()),
@@ -283,52 +751,83 @@ pub fn run() {
                 .build()?;
 
             let menu = MenuBuilder::new(app)
-                .items(&[&file_menu, &edit_menu, &view_menu, &help_menu])
+                .items(&[&file_menu, &edit_menu, &view_menu, &settings_menu, &help_menu])
                 .build()?;
 
             app.set_menu(menu)?;
+            menu_visibility::apply(&main_window);
+
+            app.manage(shortcuts::ShortcutMenuItems(
+                [
+                    &settings_item,
+                    &quit_menu_item,
+                    &reload_item,
+                    &hard_reload_item,
+                    &devtools_item,
+                    &zoom_in_item,
+                    &zoom_out_item,
+                    &zoom_reset_item,
+                    &fullscreen_item,
+                ]
+                .into_iter()
+                .map(|item| (item.id().0.clone(), item.clone()))
+                .collect(),
+            ));
 
             // ── Menu Event Handler ──────────────────────────────────
 
-            let zoom_level = Arc::new(Mutex::new(1.0_f64));
-            let minimize_for_menu = minimize_to_tray.clone();
-
             app.on_menu_event(move |app_handle, event| {
                 let id = event.id().as_ref();
+                if id.starts_with(context_menu::ID_PREFIX) {
+                    context_menu::handle_click(app_handle, id);
+                    return;
+                }
                 if let Some(window) = app_handle.get_webview_window("main") {
                     match id {
-                        "reload" => {
-                            let _ = window.eval("window.location.reload()");
-                        }
-                        "toggle_devtools" => {
+                        "reload" => reload::soft(&window),
+                        "hard_reload" => reload::hard(&window),
+                        "toggle_devtools" if devtools::enabled() => {
                             if window.is_devtools_open() {
                                 window.close_devtools();
                             } else {
                                 window.open_devtools();
                             }
                         }
-                        "zoom_in" => {
-                            let mut level = zoom_level.lock().unwrap();
-                            *level = (*level + 0.1).min(3.0);
-                            let _ = window.set_zoom(*level);
-                        }
-                        "zoom_out" => {
-                            let mut level = zoom_level.lock().unwrap();
-                            *level = (*level - 0.1).max(0.5);
-                            let _ = window.set_zoom(*level);
-                        }
-                        "zoom_reset" => {
-                            let mut level = zoom_level.lock().unwrap();
-                            *level = 1.0;
-                            let _ = window.set_zoom(1.0);
+                        "zoom_in" | "zoom_out" | "zoom_reset" => {
+                            // Zoom only the window the shortcut was pressed in
+                            let target = focused_window(app_handle, window);
+                            match id {
+                                "zoom_in" => zoom::step(&target, 1),
+                                "zoom_out" => zoom::step(&target, -1),
+                                _ => zoom::reset(&target),
+                            }
                         }
                         "toggle_fullscreen" => {
+                            video_fullscreen::forget(&window);
                             let is_fs = window.is_fullscreen().unwrap_or(false);
                             let _ = window.set_fullscreen(!is_fs);
                         }
-                        "check_updates" => {
-                            // Emit event to frontend to trigger update check
-                            let _ = window.eval("window.__NEXUS_CHECK_UPDATES && window.__NEXUS_CHECK_UPDATES()");
+                        "toggle_always_on_top" => {
+                            let enabled = !app_handle.state::<settings::SettingsStore>().get().always_on_top;
+                            let _ = always_on_top::set_enabled(app_handle, enabled);
+                        }
+                        "toggle_autostart" => {
+                            // The check item has already toggled itself; set_enabled
+                            // re-syncs it with what the OS actually reports
+                            let enabled = !autostart::is_enabled(app_handle);
+                            if let Err(e) = autostart::set_enabled(app_handle, enabled) {
+                                log::warn!("failed to update launch at login: {e}");
+                            }
+                        }
+                        "check_updates" => updater::check_from_menu(app_handle),
+                        "copy_diagnostics" => diagnostics::copy_to_clipboard(app_handle),
+                        "export_logs" => {
+                            let app = app_handle.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = logging::export_logs(app).await {
+                                    log::error!("failed to export logs: {e}");
+                                }
+                            });
                         }
                         "open_settings" => {
                             let _ = window.eval("window.__NEXUS_OPEN_SETTINGS && window.__NEXUS_OPEN_SETTINGS()");
@@ -337,18 +836,9 @@ pub fn run() {
                             let _ = window.eval("window.__NEXUS_CHANGE_SERVER && window.__NEXUS_CHANGE_SERVER()");
                         }
                         "toggle_minimize_to_tray" => {
-                            let mut val = minimize_for_menu.lock().unwrap();
-                            *val = !*val;
-                            let new_val = *val;
-                            drop(val);
-                            // Persist to app data dir
-                            if let Ok(dir) = app_handle.path().app_data_dir() {
-                                let _ = std::fs::create_dir_all(&dir);
-                                let _ = std::fs::write(
-                                    dir.join("minimize_to_tray.txt"),
-                                    if new_val { "true" } else { "false" },
-                                );
-                            }
+                            // The check item has already toggled itself
+                            let enabled = !close_to_tray::is_enabled(app_handle);
+                            close_to_tray::set_enabled(app_handle, enabled);
                         }
                         "quit_app" => {
                             app_handle.exit(0);
@@ -358,11 +848,27 @@ pub fn run() {
                 }
             });
 
+            startup_timing::mark("setup done");
             Ok(())
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building tauri application")
-        .run(|_app_handle, _event| {
-            // Dock/taskbar click is handled by the tray icon on_tray_icon_event handler
+        .run(|app_handle, event| {
+            // Taskbar clicks elsewhere are handled by the tray icon's
+            // on_tray_icon_event handler
+            match event {
+                #[cfg(target_os = "macos")]
+                tauri::RunEvent::Reopen { .. } => reopen(app_handle),
+                tauri::RunEvent::ExitRequested { code, api, .. } => {
+                    lifecycle::on_exit_requested(app_handle, code, &api);
+                }
+                tauri::RunEvent::Exit => {
+                    sleep_inhibit::release_all(app_handle);
+                    safe_mode::mark_clean_shutdown(app_handle);
+                    suspend::shutdown();
+                    kv_store::flush(app_handle);
+                }
+                _ => {}
+            }
         });
 }
@@ -0,0 +1,192 @@
+// App restart and quit handshakes with the frontend.
+//
+// Quitting during a call asks first. Every quit path (File > Quit, the tray,
+// Cmd+Q, the OS asking the app to exit) ends in `RunEvent::ExitRequested`,
+// which is held back while the frontend reports an active call. The frontend
+// gets `confirm-quit-during-call` and answers with `confirm_quit` or
+// `cancel_quit`; with no answer the quit goes ahead after
+// `QUIT_CONFIRM_TIMEOUT`, so a hung frontend can't keep the app alive.
+//
+// Then, on every quit, the frontend gets `app-will-quit` to save drafts and
+// the like, and the exit waits for `ready_to_quit` (or `QUIT_FLUSH_TIMEOUT`).
+// The shutdown is recorded as clean for safe mode's crash detection before
+// the app exits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, ExitRequestApi, Manager, Runtime};
+
+/// How long the frontend gets to flush state before we restart anyway.
+const RESTART_ACK_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a quit during a call waits for the user to answer.
+const QUIT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long the frontend gets to flush state before we quit anyway.
+const QUIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Default)]
+pub struct LifecycleState {
+    restart_ack: Mutex<Option<mpsc::Sender<()>>>,
+    call_active: AtomicBool,
+    /// Answer channel for a quit waiting on confirmation: true quits
+    quit_answer: Mutex<Option<mpsc::Sender<bool>>>,
+    /// Set once a quit is confirmed, so the resulting exit goes through
+    quit_confirmed: AtomicBool,
+    /// Ack channel for `app-will-quit` while the frontend is flushing
+    quit_ack: Mutex<Option<mpsc::Sender<()>>>,
+    /// Set once the frontend has flushed, so the resulting exit goes through
+    quit_flushed: AtomicBool,
+}
+
+#[derive(Clone, Serialize)]
+struct BeforeRestart {
+    /// The frontend should leave any voice channel before acking so the
+    /// server doesn't keep a ghost session around after the relaunch.
+    disconnect_voice: bool,
+}
+
+/// Relaunch the app. Emits `before-restart` so the frontend can flush state
+/// and disconnect voice, then waits for `restart_ready` (or the timeout).
+#[tauri::command]
+pub async fn restart_app(app: AppHandle) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    *app.state::<LifecycleState>().restart_ack.lock().unwrap() = Some(tx);
+
+    app.emit("before-restart", BeforeRestart { disconnect_voice: true })
+        .map_err(|e| e.to_string())?;
+
+    let acked = tauri::async_runtime::spawn_blocking(move || rx.recv_timeout(RESTART_ACK_TIMEOUT))
+        .await
+        .map_err(|e| e.to_string())?
+        .is_ok();
+    if !acked {
+        log::warn!("frontend didn't ack restart within {RESTART_ACK_TIMEOUT:?}, restarting anyway");
+    }
+    app.restart()
+}
+
+/// Frontend ack for `before-restart`.
+#[tauri::command]
+pub fn restart_ready(app: AppHandle) {
+    if let Some(tx) = app.state::<LifecycleState>().restart_ack.lock().unwrap().take() {
+        let _ = tx.send(());
+    }
+}
+
+/// `RunEvent::ExitRequested` hook: hold the exit back during a call until the
+/// user confirms, then until the frontend has flushed.
+pub fn on_exit_requested<R: Runtime>(app: &AppHandle<R>, code: Option<i32>, api: &ExitRequestApi) {
+    // Restarts have their own handshake in `restart_app`
+    if code == Some(tauri::RESTART_EXIT_CODE) {
+        return;
+    }
+    let state = app.state::<LifecycleState>();
+    if state.call_active.load(Ordering::SeqCst) && !state.quit_confirmed.load(Ordering::SeqCst) {
+        api.prevent_exit();
+        ask_to_quit(app);
+        return;
+    }
+    if state.quit_flushed.load(Ordering::SeqCst) {
+        return;
+    }
+    api.prevent_exit();
+    flush_then_exit(app, code.unwrap_or(0));
+}
+
+fn ask_to_quit<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<LifecycleState>();
+    let mut answer = state.quit_answer.lock().unwrap();
+    if answer.is_some() {
+        // Already asking
+        return;
+    }
+    let (tx, rx) = mpsc::channel();
+    *answer = Some(tx);
+    drop(answer);
+
+    crate::show_main_window(app);
+    let _ = app.emit("confirm-quit-during-call", ());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let quit = match rx.recv_timeout(QUIT_CONFIRM_TIMEOUT) {
+            Ok(quit) => quit,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::warn!("quit wasn't confirmed within {QUIT_CONFIRM_TIMEOUT:?}, quitting anyway");
+                true
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => false,
+        };
+        let state = app.state::<LifecycleState>();
+        state.quit_answer.lock().unwrap().take();
+        if quit {
+            state.quit_confirmed.store(true, Ordering::SeqCst);
+            app.exit(0);
+        }
+    });
+}
+
+/// Give the frontend its chance to save state, then exit with `code`.
+fn flush_then_exit<R: Runtime>(app: &AppHandle<R>, code: i32) {
+    let state = app.state::<LifecycleState>();
+    let mut ack = state.quit_ack.lock().unwrap();
+    if ack.is_some() {
+        // Already flushing
+        return;
+    }
+    let (tx, rx) = mpsc::channel();
+    *ack = Some(tx);
+    drop(ack);
+
+    let _ = app.emit("app-will-quit", ());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if rx.recv_timeout(QUIT_FLUSH_TIMEOUT).is_err() {
+            log::warn!("frontend didn't ack quit within {QUIT_FLUSH_TIMEOUT:?}, quitting anyway");
+        }
+        crate::safe_mode::mark_clean_shutdown(&app);
+        let state = app.state::<LifecycleState>();
+        state.quit_flushed.store(true, Ordering::SeqCst);
+        state.quit_ack.lock().unwrap().take();
+        app.exit(code);
+    });
+}
+
+/// Frontend ack for `app-will-quit`.
+#[tauri::command]
+pub fn ready_to_quit(app: AppHandle) {
+    // Left in place so a second quit during the flush doesn't start another
+    let ack = app.state::<LifecycleState>().quit_ack.lock().unwrap().clone();
+    if let Some(tx) = ack {
+        let _ = tx.send(());
+    }
+}
+
+/// Tell the backend whether a call is in progress, so quitting asks first.
+#[tauri::command]
+pub fn set_call_active(app: AppHandle, active: bool) {
+    app.state::<LifecycleState>()
+        .call_active
+        .store(active, Ordering::SeqCst);
+}
+
+fn answer_quit(app: &AppHandle, quit: bool) {
+    let answer = app.state::<LifecycleState>().quit_answer.lock().unwrap().clone();
+    if let Some(tx) = answer {
+        let _ = tx.send(quit);
+    }
+}
+
+/// Answer `confirm-quit-during-call`: quit now.
+#[tauri::command]
+pub fn confirm_quit(app: AppHandle) {
+    answer_quit(&app, true);
+}
+
+/// Answer `confirm-quit-during-call`: stay open.
+#[tauri::command]
+pub fn cancel_quit(app: AppHandle) {
+    answer_quit(&app, false);
+}
@@ -0,0 +1,220 @@
+// Recovery when the frontend fails to load. Instead of a blank window, the
+// webview is sent to a bundled error page (`offline.html`, served from the
+// binary over `nexus-offline://` so it works when the assets or dev server
+// are the thing that's broken) with a Retry button that calls `reload`.
+// Every failure is also emitted as `webview-load-failed { url, error }`.
+//
+// Failures are picked up from WebKitGTK's `load-failed` signal and
+// WebView2's NavigationCompleted. WKWebView's navigation delegate belongs to
+// wry, so on macOS only the timeout applies: a load that hasn't finished
+// after `LOAD_TIMEOUT` counts as failed on every platform.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::http::{header, Request, Response};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{Emitter, Manager, Runtime, Url, WebviewWindow};
+
+pub const SCHEME: &str = "nexus-offline";
+
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const OFFLINE_PAGE: &[u8] = include_bytes!("../offline.html");
+
+#[derive(Default)]
+struct Watch {
+    /// Bumped on every load, so a timer only fires for the load it was
+    /// started for
+    generation: u64,
+    loading: bool,
+    /// What Retry goes back to
+    failed_url: Option<Url>,
+}
+
+#[derive(Default)]
+pub struct LoadWatchState(Mutex<HashMap<String, Watch>>);
+
+#[derive(Clone, Serialize)]
+struct WebviewLoadFailed {
+    url: String,
+    error: String,
+}
+
+pub(crate) fn is_offline_page(url: &Url) -> bool {
+    url.scheme() == SCHEME || url.host_str() == Some("nexus-offline.localhost")
+}
+
+fn offline_url(url: &str, error: &str) -> Url {
+    // Custom protocols are served from http://<scheme>.localhost on Windows
+    #[cfg(target_os = "windows")]
+    let base = format!("http://{SCHEME}.localhost/");
+    #[cfg(not(target_os = "windows"))]
+    let base = format!("{SCHEME}://localhost/");
+    let mut page = Url::parse(&base).expect("offline page URL is valid");
+    page.query_pairs_mut()
+        .append_pair("url", url)
+        .append_pair("error", error);
+    page
+}
+
+fn fail<R: Runtime>(window: &WebviewWindow<R>, url: &str, error: &str) {
+    let parsed = Url::parse(url).ok();
+    if parsed.as_ref().is_some_and(is_offline_page) {
+        return;
+    }
+    if let Some(state) = window.app_handle().try_state::<LoadWatchState>() {
+        let mut watches = state.0.lock().unwrap();
+        let watch = watches.entry(window.label().to_string()).or_default();
+        watch.loading = false;
+        if parsed.is_some() {
+            watch.failed_url = parsed;
+        }
+    }
+    log::warn!("webview failed to load {url}: {error}");
+    let _ = window.emit_to(
+        window.label(),
+        "webview-load-failed",
+        WebviewLoadFailed {
+            url: url.to_string(),
+            error: error.to_string(),
+        },
+    );
+    if let Err(e) = window.navigate(offline_url(url, error)) {
+        log::warn!("failed to show the offline page: {e}");
+    }
+}
+
+/// Page load hook for the window builders' `on_page_load`; runs the timeout.
+pub fn on_page_load<R: Runtime>(window: &WebviewWindow<R>, payload: &PageLoadPayload<'_>) {
+    if is_offline_page(payload.url()) {
+        return;
+    }
+    let Some(state) = window.app_handle().try_state::<LoadWatchState>() else {
+        return;
+    };
+    let mut watches = state.0.lock().unwrap();
+    let watch = watches.entry(window.label().to_string()).or_default();
+    match payload.event() {
+        PageLoadEvent::Started => {
+            watch.generation += 1;
+            watch.loading = true;
+            let generation = watch.generation;
+            let window = window.clone();
+            let url = payload.url().to_string();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(LOAD_TIMEOUT).await;
+                let timed_out = window
+                    .app_handle()
+                    .state::<LoadWatchState>()
+                    .0
+                    .lock()
+                    .unwrap()
+                    .get(window.label())
+                    .is_some_and(|w| w.loading && w.generation == generation);
+                if timed_out {
+                    fail(&window, &url, "timed out");
+                }
+            });
+        }
+        PageLoadEvent::Finished => watch.loading = false,
+    }
+}
+
+/// Hook the webview's own load-failure notifications. Call once per window.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        let w = window.clone();
+        window.with_webview(move |webview| {
+            use webkit2gtk::{NetworkError, PolicyError, WebViewExt};
+
+            webview.inner().connect_load_failed(move |_wv, _event, uri, error| {
+                // Superseded navigations and downloads aren't failures
+                if error.matches(NetworkError::Cancelled)
+                    || error.matches(PolicyError::FrameLoadInterruptedByPolicyChange)
+                {
+                    return false;
+                }
+                fail(&w, uri, &error.to_string());
+                true
+            });
+        })?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let w = window.clone();
+        window.with_webview(move |webview| {
+            unsafe {
+                use webview2_com::Microsoft::Web::WebView2::Win32::*;
+                use webview2_com::NavigationCompletedEventHandler;
+                use windows::core::{BOOL, PWSTR};
+
+                let core = webview.controller().CoreWebView2().unwrap();
+                let handler = NavigationCompletedEventHandler::create(Box::new(
+                    move |sender, args| {
+                        let (Some(core), Some(args)) = (sender, args) else {
+                            return Ok(());
+                        };
+                        let mut success = BOOL::default();
+                        args.IsSuccess(&mut success)?;
+                        let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+                        args.WebErrorStatus(&mut status)?;
+                        if success.as_bool()
+                            || status == COREWEBVIEW2_WEB_ERROR_STATUS_OPERATION_CANCELED
+                        {
+                            return Ok(());
+                        }
+                        let mut source = PWSTR::null();
+                        core.Source(&mut source)?;
+                        let url = webview2_com::take_pwstr(source);
+                        fail(&w, &url, &format!("navigation failed (status {})", status.0));
+                        Ok(())
+                    },
+                ));
+                let mut token = std::mem::zeroed();
+                core.add_NavigationCompleted(&handler, &mut token)
+                    .unwrap();
+            }
+        })?;
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = window;
+    Ok(())
+}
+
+/// Handler for `register_uri_scheme_protocol`: serves the offline page.
+pub fn protocol<R: Runtime>(
+    _ctx: tauri::UriSchemeContext<'_, R>,
+    _request: Request<Vec<u8>>,
+) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(Cow::Borrowed(OFFLINE_PAGE))
+        .unwrap_or_default()
+}
+
+/// Retry after a failed load: go back to the page that failed, or reload
+/// the current one.
+#[tauri::command]
+pub fn reload(window: WebviewWindow) -> Result<(), String> {
+    let failed_url = window
+        .app_handle()
+        .state::<LoadWatchState>()
+        .0
+        .lock()
+        .unwrap()
+        .get_mut(window.label())
+        .and_then(|w| w.failed_url.take());
+    match failed_url {
+        Some(url) => window.navigate(url).map_err(|e| e.to_string()),
+        None => {
+            crate::reload::soft(&window);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// The OS locale and preferred languages as BCP 47 tags (`en-US`), for
+// picking the UI language before the user has chosen one.
+//
+// macOS and Windows let the language list change while the app runs, so a
+// background thread polls it there and emits `locale-changed` with the new
+// `Locale`. On Linux it comes from the process environment, which can't
+// change underneath us.
+
+#[cfg(not(target_os = "linux"))]
+use serde::Serialize;
+
+/// The payload of `locale-changed`.
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Locale {
+    pub locale: Option<String>,
+    /// Most preferred first
+    pub languages: Vec<String>,
+}
+
+/// `en_US.UTF-8` / `de_DE@euro` → `en-US` / `de-DE`. The C locale isn't a
+/// language.
+fn normalize(raw: &str) -> Option<String> {
+    let tag = raw.split(['.', '@']).next()?.trim().replace('_', "-");
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag)
+}
+
+/// The OS locale.
+pub fn current() -> Option<String> {
+    sys_locale::get_locale().as_deref().and_then(normalize)
+}
+
+/// The user's languages in order of preference, without duplicates.
+pub fn preferred() -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for tag in sys_locale::get_locales().filter_map(|l| normalize(&l)) {
+        if !languages.contains(&tag) {
+            languages.push(tag);
+        }
+    }
+    languages
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read() -> Locale {
+    Locale {
+        locale: current(),
+        languages: preferred(),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn poll_loop<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    use tauri::Emitter;
+
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+    let mut last = read();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = read();
+        if current != last {
+            log::info!("OS locale changed to {:?}", current.locale);
+            let _ = app.emit("locale-changed", &current);
+            last = current;
+        }
+    }
+}
+
+/// Start watching for locale changes. Started by `deferred_init`.
+pub fn init<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    #[cfg(not(target_os = "linux"))]
+    {
+        let handle = app.clone();
+        if let Err(e) = std::thread::Builder::new()
+            .name("locale-monitor".into())
+            .spawn(move || poll_loop(handle))
+        {
+            log::warn!("failed to start locale monitor: {e}");
+        }
+    }
+    #[cfg(target_os = "linux")]
+    let _ = app;
+}
+
+#[tauri::command]
+pub fn get_locale() -> Option<String> {
+    current()
+}
+
+#[tauri::command]
+pub fn get_preferred_languages() -> Vec<String> {
+    preferred()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_strips_encoding_and_modifier() {
+        assert_eq!(normalize("en_US.UTF-8").as_deref(), Some("en-US"));
+        assert_eq!(normalize("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(normalize("sr_RS.UTF-8@latin").as_deref(), Some("sr-RS"));
+    }
+
+    #[test]
+    fn normalize_keeps_bcp47_tags() {
+        assert_eq!(normalize("en-GB").as_deref(), Some("en-GB"));
+        assert_eq!(normalize("fr").as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn normalize_rejects_c_locale_and_empty() {
+        assert_eq!(normalize("C"), None);
+        assert_eq!(normalize("C.UTF-8"), None);
+        assert_eq!(normalize("POSIX"), None);
+        assert_eq!(normalize(""), None);
+    }
+}
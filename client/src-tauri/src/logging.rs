@@ -0,0 +1,120 @@
+// Application logging. Logs go to stdout and to a rotating file in the app
+// log dir (in release builds too) so users can attach them to bug reports.
+//
+// The level comes from `NEXUS_LOG` if set, else from settings, and can be
+// changed at runtime with `set_log_level`. The plugin itself is built to
+// pass everything; the effective level is the `log` crate's global max
+// level, which can be changed at any time without rebuilding the logger.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+/// Size at which the active log file is rotated. Together with
+/// `KEPT_LOG_FILES` this caps retained logs at about 10 MB.
+const MAX_LOG_FILE_BYTES: u128 = 2 * 1024 * 1024;
+const KEPT_LOG_FILES: usize = 4;
+
+const LEVEL_ENV: &str = "NEXUS_LOG";
+
+pub fn plugin<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    let file_target = match crate::paths::overridden() {
+        Some(root) => TargetKind::Folder {
+            path: root.join("logs"),
+            file_name: None,
+        },
+        None => TargetKind::LogDir { file_name: None },
+    };
+    tauri_plugin_log::Builder::default()
+        .level(LevelFilter::Trace)
+        .targets([Target::new(TargetKind::Stdout), Target::new(file_target)])
+        .max_file_size(MAX_LOG_FILE_BYTES)
+        .rotation_strategy(RotationStrategy::KeepSome(KEPT_LOG_FILES))
+        .build()
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level.trim().parse().map_err(|_| {
+        format!("unknown log level \"{level}\" (expected off, error, warn, info, debug or trace)")
+    })
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_lowercase()
+}
+
+/// Apply the configured level. Called from `setup`, after the plugin has
+/// installed the logger.
+pub fn init(app: &tauri::App) {
+    let from_settings = app.state::<crate::settings::SettingsStore>().get().log_level;
+    let level = match std::env::var(LEVEL_ENV) {
+        Ok(env) => parse_level(&env).unwrap_or_else(|e| {
+            log::warn!("ignoring {LEVEL_ENV}: {e}");
+            parse_level(&from_settings).unwrap_or(LevelFilter::Info)
+        }),
+        Err(_) => parse_level(&from_settings).unwrap_or(LevelFilter::Info),
+    };
+    log::set_max_level(level);
+}
+
+fn write_zip(log_dir: &std::path::Path, dest: &std::path::Path) -> Result<(), String> {
+    let file = std::fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let entries = std::fs::read_dir(log_dir).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let contents = std::fs::read(&path).map_err(|e| e.to_string())?;
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&contents).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Change and persist the log level. Returns the level now in effect.
+#[tauri::command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<String, String> {
+    let level = parse_level(&level)?;
+    log::set_max_level(level);
+    app.state::<crate::settings::SettingsStore>()
+        .update(&app, |s| s.log_level = level_name(level));
+    log::info!("log level set to {}", level_name(level));
+    Ok(level_name(log::max_level()))
+}
+
+/// Ask the user where to save, then zip the current and rotated log files
+/// there. Returns the saved path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_logs(app: AppHandle) -> Result<Option<PathBuf>, String> {
+    let log_dir = crate::paths::log_dir(&app).map_err(|e| e.to_string())?;
+    let Some(dest) = app
+        .dialog()
+        .file()
+        .set_file_name("nexus-logs.zip")
+        .add_filter("Zip archive", &["zip"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let dest = dest.into_path().map_err(|e| e.to_string())?;
+
+    let out = dest.clone();
+    tauri::async_runtime::spawn_blocking(move || write_zip(&log_dir, &out))
+        .await
+        .map_err(|e| e.to_string())??;
+    log::info!("exported logs to {}", dest.display());
+    Ok(Some(dest))
+}
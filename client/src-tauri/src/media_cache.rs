@@ -0,0 +1,291 @@
+// `nexus-media://` — avatars, attachments and other remote media served from
+// an on-disk cache. The webview requests
+// `nexus-media://localhost/?url=<encoded https URL>` (on Windows
+// `http://nexus-media.localhost/?url=...`); a miss is downloaded through the
+// configured proxy and cached, so the next launch doesn't fetch it again.
+//
+// Entries are keyed by a SHA-256 of the URL, with the content type in a
+// `.meta` sidecar. File mtimes double as the LRU clock: hits touch the file
+// and eviction removes the oldest entries once the cache is over
+// `MAX_CACHE_BYTES`. Range requests are supported so audio/video can seek.
+
+use std::fmt::Write;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Runtime, Url};
+use tokio::io::AsyncWriteExt;
+
+pub const SCHEME: &str = "nexus-media";
+
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+/// Largest slice served for an open-ended range, so seeking in a long
+/// video doesn't read the rest of the file into memory
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+const META_EXT: &str = "meta";
+
+/// Numbers the temporary files, so concurrent downloads of the same URL
+/// don't write to the same one.
+static NEXT_PART: AtomicU64 = AtomicU64::new(0);
+
+fn cache_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = crate::paths::cache_dir(app)
+        .map_err(|e| e.to_string())?
+        .join("media");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+fn response(status: StatusCode, body: impl Into<Vec<u8>>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(body.into())
+        .unwrap_or_default()
+}
+
+/// The remote URL a request is for. Only http(s) URLs are fetched.
+fn source_url(request: &Request<Vec<u8>>) -> Option<String> {
+    let uri = Url::parse(&request.uri().to_string()).ok()?;
+    let (_, url) = uri.query_pairs().find(|(k, _)| k == "url")?;
+    let parsed = Url::parse(&url).ok()?;
+    matches!(parsed.scheme(), "http" | "https").then(|| url.into_owned())
+}
+
+/// Download `url` into the cache. Written under a temporary name of its own
+/// first, so a failed download never leaves a truncated entry behind and a
+/// second download of the same URL just replaces the entry.
+async fn download<R: Runtime>(app: &AppHandle<R>, url: &str, path: &Path) -> Result<(), String> {
+    let mut response = crate::http::client(app)?
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            mime_guess::from_path(Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default())
+                .first_or_octet_stream()
+                .to_string()
+        });
+
+    let part = path.with_extension(format!(
+        "part-{}-{}",
+        std::process::id(),
+        NEXT_PART.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result: Result<(), String> = async {
+        let mut file = tokio::fs::File::create(&part)
+            .await
+            .map_err(|e| e.to_string())?;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        tokio::fs::write(path.with_extension(META_EXT), content_type)
+            .await
+            .map_err(|e| e.to_string())?;
+        tokio::fs::rename(&part, path)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&part).await;
+    }
+    result
+}
+
+/// Remove the least recently used entries until the cache fits.
+fn evict(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_none())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((meta.modified().ok()?, meta.len(), e.path()))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= MAX_CACHE_BYTES {
+        return;
+    }
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in files {
+        if total <= MAX_CACHE_BYTES {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            let _ = std::fs::remove_file(path.with_extension(META_EXT));
+            total -= size;
+        }
+    }
+}
+
+/// Parse a single `bytes=start-end` range against a file of `len` bytes.
+/// Multi-range requests are served as a single range from the first one.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let n: u64 = end.parse().ok()?;
+        (len.saturating_sub(n), len.checked_sub(1)?)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = match end {
+            "" => start.saturating_add(MAX_RANGE_BYTES - 1).min(len.checked_sub(1)?),
+            end => end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        };
+        (start, end)
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn serve(path: &Path, range: Option<&str>) -> Result<Response<Vec<u8>>, String> {
+    let content_type = std::fs::read_to_string(path.with_extension(META_EXT))
+        .unwrap_or_else(|_| "application/octet-stream".into());
+    // Opened writable because Windows needs that to update the mtime
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    // Touch for LRU
+    let _ = file.set_modified(SystemTime::now());
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, "max-age=31536000, immutable");
+    let Some(range) = range else {
+        let mut body = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut body).map_err(|e| e.to_string())?;
+        return builder
+            .status(StatusCode::OK)
+            .body(body)
+            .map_err(|e| e.to_string());
+    };
+    let Some((start, end)) = parse_range(range, len) else {
+        return builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Vec::new())
+            .map_err(|e| e.to_string());
+    };
+    let mut body = vec![0; (end - start + 1) as usize];
+    file.seek(SeekFrom::Start(start)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut body).map_err(|e| e.to_string())?;
+    builder
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+        .body(body)
+        .map_err(|e| e.to_string())
+}
+
+async fn handle<R: Runtime>(app: AppHandle<R>, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Some(url) = source_url(&request) else {
+        return response(StatusCode::BAD_REQUEST, "expected ?url=<http(s) URL>");
+    };
+    let dir = match cache_dir(&app) {
+        Ok(dir) => dir,
+        Err(e) => return response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    let path = dir.join(key(&url));
+    if !path.exists() {
+        if let Err(e) = download(&app, &url, &path).await {
+            log::warn!("failed to fetch media {url}: {e}");
+            return response(StatusCode::BAD_GATEWAY, e);
+        }
+        let dir = dir.clone();
+        tauri::async_runtime::spawn_blocking(move || evict(&dir));
+    }
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    tauri::async_runtime::spawn_blocking(move || serve(&path, range.as_deref()))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+        .unwrap_or_else(|e| response(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// Handler for `register_asynchronous_uri_scheme_protocol`.
+pub fn protocol<R: Runtime>(
+    ctx: tauri::UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: tauri::UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        responder.respond(handle(app, request).await);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_bounded() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=900-2000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=5-5", 10), Some((5, 5)));
+    }
+
+    #[test]
+    fn parse_range_open_ended_is_capped() {
+        assert_eq!(parse_range("bytes=100-", 1000), Some((100, 999)));
+        let len = 3 * MAX_RANGE_BYTES;
+        assert_eq!(parse_range("bytes=0-", len), Some((0, MAX_RANGE_BYTES - 1)));
+    }
+
+    #[test]
+    fn parse_range_suffix() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        // Longer than the file: the whole file
+        assert_eq!(parse_range("bytes=-5000", 1000), Some((0, 999)));
+    }
+
+    #[test]
+    fn parse_range_multi_uses_first() {
+        assert_eq!(parse_range("bytes=0-9, 20-29", 100), Some((0, 9)));
+    }
+
+    #[test]
+    fn parse_range_unsatisfiable() {
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=50-10", 1000), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("bytes=-10", 0), None);
+    }
+
+    #[test]
+    fn parse_range_malformed() {
+        assert_eq!(parse_range("items=0-10", 100), None);
+        assert_eq!(parse_range("bytes=abc", 100), None);
+        assert_eq!(parse_range("bytes=a-b", 100), None);
+    }
+}
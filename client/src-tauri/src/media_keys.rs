@@ -0,0 +1,112 @@
+// Hardware media keys (play/pause, next, previous, stop), forwarded to the
+// frontend as `media-key { action }`.
+//
+// The keys are grabbed through the global shortcut plugin, which means
+// nothing else receives them while they're registered. So by default they're
+// only held while a Nexus window has focus, leaving them to Spotify & co.
+// otherwise; `set_media_keys_in_background` opts in to keeping them. Where
+// the OS won't hand media keys out as hotkeys (macOS, Wayland) registration
+// fails and is logged.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+use tauri_plugin_global_shortcut::{
+    Code, GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState,
+};
+
+use crate::settings::SettingsStore;
+
+const KEYS: &[(Code, &str)] = &[
+    (Code::MediaPlayPause, "play_pause"),
+    (Code::MediaTrackNext, "next"),
+    (Code::MediaTrackPrevious, "prev"),
+    (Code::MediaStop, "stop"),
+];
+
+/// Whether the keys are currently registered.
+#[derive(Default)]
+pub struct MediaKeysState(Mutex<bool>);
+
+#[derive(Clone, Serialize)]
+struct MediaKey {
+    action: &'static str,
+}
+
+/// Global shortcut plugin handler — emits `media-key` for our keys.
+pub fn handle_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = KEYS
+        .iter()
+        .find(|(code, _)| *shortcut == Shortcut::new(None, *code))
+        .map(|(_, action)| *action);
+    if let Some(action) = action {
+        let _ = app.emit("media-key", MediaKey { action });
+    }
+}
+
+/// Register or release the keys to match settings and window focus.
+fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<MediaKeysState>() else {
+        return;
+    };
+    let settings = app.state::<SettingsStore>().get();
+    let wanted = settings.media_controls_enabled
+        && (settings.media_keys_in_background
+            || app
+                .webview_windows()
+                .values()
+                .any(|w| w.is_focused().unwrap_or(false)));
+
+    let mut registered = state.0.lock().unwrap();
+    if *registered == wanted {
+        return;
+    }
+    for (code, _) in KEYS {
+        let shortcut = Shortcut::new(None, *code);
+        let result = if wanted {
+            app.global_shortcut().register(shortcut)
+        } else {
+            app.global_shortcut().unregister(shortcut)
+        };
+        if let Err(e) = result {
+            log::warn!("failed to update media key {code:?}: {e}");
+        }
+    }
+    *registered = wanted;
+}
+
+/// Re-evaluate whenever the window gains or loses focus.
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle().clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(_) = event {
+            refresh(&app);
+        }
+    });
+}
+
+/// Set up media keys from saved settings. Called from `setup`.
+pub fn init(app: &tauri::App) {
+    app.manage(MediaKeysState::default());
+    refresh(app.handle());
+}
+
+/// Turn Nexus's media key handling on or off.
+#[tauri::command]
+pub fn set_media_controls_enabled(app: AppHandle, enabled: bool) {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.media_controls_enabled = enabled);
+    refresh(&app);
+}
+
+/// Keep the media keys while no Nexus window is focused.
+#[tauri::command]
+pub fn set_media_keys_in_background(app: AppHandle, enabled: bool) {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.media_keys_in_background = enabled);
+    refresh(&app);
+}
@@ -0,0 +1,63 @@
+// Hiding the menu bar to save space on small screens. On Windows and Linux
+// each window carries its own copy of the app menu, so the choice applies to
+// every account window and is saved for new ones. macOS has one global menu
+// bar that an app can't hide, so the command refuses there.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[cfg(not(target_os = "macos"))]
+fn apply_to<R: Runtime>(window: &WebviewWindow<R>, visible: bool) -> tauri::Result<()> {
+    if visible {
+        window.show_menu()
+    } else {
+        window.hide_menu()
+    }
+}
+
+/// Apply the saved choice to a window. Call once per account window, after
+/// the app menu is set.
+pub fn apply<R: Runtime>(window: &WebviewWindow<R>) {
+    #[cfg(not(target_os = "macos"))]
+    {
+        let visible = window.app_handle().state::<SettingsStore>().get().menu_visible;
+        if let Err(e) = apply_to(window, visible) {
+            log::warn!("failed to apply menu visibility: {e}");
+        }
+    }
+    #[cfg(target_os = "macos")]
+    let _ = window;
+}
+
+#[tauri::command]
+pub fn set_menu_visible(app: AppHandle, visible: bool) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (app, visible);
+        Err("the macOS menu bar is global and can't be hidden".into())
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        for window in app.webview_windows().values() {
+            if crate::accounts::is_account_window(window.label()) {
+                apply_to(window, visible).map_err(|e| e.to_string())?;
+            }
+        }
+        app.state::<SettingsStore>()
+            .update(&app, |s| s.menu_visible = visible);
+        Ok(())
+    }
+}
+
+/// Whether the calling window shows its menu bar. Always true on macOS.
+#[tauri::command]
+pub fn is_menu_visible(window: WebviewWindow) -> Result<bool, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = window;
+        Ok(true)
+    }
+    #[cfg(not(target_os = "macos"))]
+    window.is_menu_visible().map_err(|e| e.to_string())
+}
@@ -0,0 +1,171 @@
+// Live microphone level for the mic-test UI. The input stream lives on its
+// own thread (cpal streams aren't Send on every platform); the audio callback
+// only accumulates, and that thread emits `mic-level` every ~50ms. Dropping
+// the stream releases the device, so the OS "mic in use" indicator goes out
+// as soon as the monitor stops.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audio;
+
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, Serialize)]
+struct MicLevel {
+    rms: f32,
+    peak: f32,
+}
+
+/// Samples seen since the last emitted level.
+#[derive(Default)]
+struct Accumulator {
+    sum_squares: f64,
+    peak: f32,
+    count: usize,
+}
+
+impl Accumulator {
+    fn take(&mut self) -> MicLevel {
+        let level = MicLevel {
+            rms: if self.count == 0 {
+                0.0
+            } else {
+                (self.sum_squares / self.count as f64).sqrt() as f32
+            },
+            peak: self.peak,
+        };
+        *self = Self::default();
+        level
+    }
+}
+
+struct Monitor {
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Default)]
+pub struct MicMonitorState(Mutex<Option<Monitor>>);
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    acc: Arc<Mutex<Accumulator>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _| {
+            let mut acc = acc.lock().unwrap();
+            for &sample in data {
+                let s = sample.to_sample::<f32>();
+                acc.sum_squares += (s * s) as f64;
+                acc.peak = acc.peak.max(s.abs());
+            }
+            acc.count += data.len();
+        },
+        |e| log::warn!("mic monitor stream error: {e}"),
+        None,
+    )
+}
+
+fn open_stream(
+    device_id: Option<&str>,
+    acc: Arc<Mutex<Accumulator>>,
+) -> Result<cpal::Stream, String> {
+    let device = audio::find_device(device_id, true).ok_or("input device not found")?;
+    let supported = device.default_input_config().map_err(|e| e.to_string())?;
+    let config = supported.config();
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, acc),
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, acc),
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, acc),
+        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, acc),
+        format => return Err(format!("unsupported sample format {format:?}")),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    Ok(stream)
+}
+
+fn stop(state: &MicMonitorState) {
+    let monitor = state.0.lock().unwrap().take();
+    if let Some(monitor) = monitor {
+        let _ = monitor.stop.send(());
+        let _ = monitor.thread.join();
+    }
+}
+
+fn start(app: AppHandle, device_id: Option<String>) -> Result<(), String> {
+    let state = app.state::<MicMonitorState>();
+    stop(&state);
+
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+    let app_for_thread = app.clone();
+    let thread = std::thread::Builder::new()
+        .name("mic-monitor".into())
+        .spawn(move || {
+            let acc = Arc::new(Mutex::new(Accumulator::default()));
+            let stream = match open_stream(device_id.as_deref(), acc.clone()) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready_tx.send(Ok(()));
+            // Until stopped, or replaced by a newer monitor
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(LEVEL_INTERVAL) {
+                let level = acc.lock().unwrap().take();
+                let _ = app_for_thread.emit("mic-level", level);
+            }
+            drop(stream);
+        })
+        .map_err(|e| e.to_string())?;
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => {
+            *state.0.lock().unwrap() = Some(Monitor {
+                stop: stop_tx,
+                thread,
+            });
+            Ok(())
+        }
+        Ok(Err(e)) => {
+            let _ = thread.join();
+            Err(e)
+        }
+        Err(_) => Err("mic monitor thread exited unexpectedly".into()),
+    }
+}
+
+/// Start emitting `mic-level { rms, peak }` for `device_id` (the default
+/// input when omitted), replacing any monitor already running.
+#[tauri::command]
+pub async fn start_mic_monitor(app: AppHandle, device_id: Option<String>) -> Result<(), String> {
+    // Opening a device can block for a while (ALSA especially)
+    tauri::async_runtime::spawn_blocking(move || start(app, device_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Stop the running monitor and release the input device. Returns once the
+/// stream has been closed.
+#[tauri::command]
+pub async fn stop_mic_monitor(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || stop(&app.state::<MicMonitorState>()))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,256 @@
+// Mute the default input device at the OS level, so the system's mic
+// indicator goes off too, not just our outgoing audio.
+//
+// - macOS: the CoreAudio mute property of the default input device.
+// - Windows: IAudioEndpointVolume on the default capture endpoint.
+// - Linux: `pactl` on the default source, which covers PulseAudio and
+//   PipeWire's Pulse server.
+//
+// Not every device exposes a mute control. Callers are told when OS mute is
+// unavailable so they can fall back to muting in software. A background
+// thread polls the state and emits `mic-mute-changed { muted }` when it
+// changes, including from outside Nexus (a keyboard mic key, system
+// settings).
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+
+    const fn fourcc(code: &[u8; 4]) -> u32 {
+        u32::from_be_bytes(*code)
+    }
+
+    const K_AUDIO_OBJECT_SYSTEM_OBJECT: u32 = 1;
+    const K_AUDIO_HARDWARE_PROPERTY_DEFAULT_INPUT_DEVICE: u32 = fourcc(b"dIn ");
+    const K_AUDIO_DEVICE_PROPERTY_MUTE: u32 = fourcc(b"mute");
+    const K_AUDIO_OBJECT_PROPERTY_SCOPE_GLOBAL: u32 = fourcc(b"glob");
+    const K_AUDIO_DEVICE_PROPERTY_SCOPE_INPUT: u32 = fourcc(b"inpt");
+    const K_AUDIO_OBJECT_PROPERTY_ELEMENT_MAIN: u32 = 0;
+
+    #[repr(C)]
+    struct AudioObjectPropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectHasProperty(id: u32, address: *const AudioObjectPropertyAddress) -> u8;
+        fn AudioObjectGetPropertyData(
+            id: u32,
+            address: *const AudioObjectPropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+        fn AudioObjectSetPropertyData(
+            id: u32,
+            address: *const AudioObjectPropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: u32,
+            data: *const c_void,
+        ) -> i32;
+    }
+
+    fn get_u32(id: u32, address: &AudioObjectPropertyAddress) -> Result<u32, String> {
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                id,
+                address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut u32 as *mut c_void,
+            )
+        };
+        if status == 0 {
+            Ok(value)
+        } else {
+            Err(format!("AudioObjectGetPropertyData failed ({status})"))
+        }
+    }
+
+    /// The default input device, if it has a mute control.
+    fn mute_address() -> Result<(u32, AudioObjectPropertyAddress), String> {
+        let device = get_u32(
+            K_AUDIO_OBJECT_SYSTEM_OBJECT,
+            &AudioObjectPropertyAddress {
+                selector: K_AUDIO_HARDWARE_PROPERTY_DEFAULT_INPUT_DEVICE,
+                scope: K_AUDIO_OBJECT_PROPERTY_SCOPE_GLOBAL,
+                element: K_AUDIO_OBJECT_PROPERTY_ELEMENT_MAIN,
+            },
+        )?;
+        let address = AudioObjectPropertyAddress {
+            selector: K_AUDIO_DEVICE_PROPERTY_MUTE,
+            scope: K_AUDIO_DEVICE_PROPERTY_SCOPE_INPUT,
+            element: K_AUDIO_OBJECT_PROPERTY_ELEMENT_MAIN,
+        };
+        if unsafe { AudioObjectHasProperty(device, &address) } == 0 {
+            return Err("input device has no mute control".into());
+        }
+        Ok((device, address))
+    }
+
+    pub fn is_muted() -> Result<bool, String> {
+        let (device, address) = mute_address()?;
+        get_u32(device, &address).map(|v| v != 0)
+    }
+
+    pub fn set_muted(muted: bool) -> Result<(), String> {
+        let (device, address) = mute_address()?;
+        let value: u32 = muted.into();
+        let status = unsafe {
+            AudioObjectSetPropertyData(
+                device,
+                &address,
+                0,
+                std::ptr::null(),
+                std::mem::size_of::<u32>() as u32,
+                &value as *const u32 as *const c_void,
+            )
+        };
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(format!("AudioObjectSetPropertyData failed ({status})"))
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+    use windows::Win32::Media::Audio::{eCapture, eConsole, IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    fn endpoint() -> Result<IAudioEndpointVolume, String> {
+        unsafe {
+            // Harmless if this thread already joined the apartment
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                    .map_err(|e| e.to_string())?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eCapture, eConsole)
+                .map_err(|e| e.to_string())?;
+            device
+                .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    pub fn is_muted() -> Result<bool, String> {
+        let muted = unsafe { endpoint()?.GetMute() }.map_err(|e| e.to_string())?;
+        Ok(muted.as_bool())
+    }
+
+    pub fn set_muted(muted: bool) -> Result<(), String> {
+        unsafe { endpoint()?.SetMute(muted, std::ptr::null()) }.map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    fn pactl(args: &[&str]) -> Result<String, String> {
+        let output = Command::new("pactl")
+            .args(args)
+            .output()
+            .map_err(|e| format!("pactl unavailable: {e}"))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub fn is_muted() -> Result<bool, String> {
+        // "Mute: yes" / "Mute: no"
+        let output = pactl(&["get-source-mute", "@DEFAULT_SOURCE@"])?;
+        match output.split_once(':').map(|(_, v)| v.trim()) {
+            Some("yes") => Ok(true),
+            Some("no") => Ok(false),
+            _ => Err(format!("unexpected pactl output: {}", output.trim())),
+        }
+    }
+
+    pub fn set_muted(muted: bool) -> Result<(), String> {
+        let value = if muted { "1" } else { "0" };
+        pactl(&["set-source-mute", "@DEFAULT_SOURCE@", value]).map(|_| ())
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct MicMuteChanged {
+    muted: bool,
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    let mut last = match platform::is_muted() {
+        Ok(muted) => muted,
+        Err(e) => {
+            log::info!("OS mic mute unavailable, not watching it: {e}");
+            return;
+        }
+    };
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        // The default device can change under us; skip reads that fail
+        let Ok(muted) = platform::is_muted() else {
+            continue;
+        };
+        if muted != last {
+            last = muted;
+            let _ = app.emit("mic-mute-changed", MicMuteChanged { muted });
+        }
+    }
+}
+
+/// Start watching the OS mute state. Started by `deferred_init`.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("mic-mute-monitor".into())
+        .spawn(move || poll_loop(handle))
+    {
+        log::warn!("failed to start mic mute monitor: {e}");
+    }
+}
+
+/// Mute or unmute the default input device. Returns false when the OS mute
+/// isn't available for it, in which case the caller should mute in software.
+#[tauri::command]
+pub async fn set_mic_muted(muted: bool) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || match platform::set_muted(muted) {
+        Ok(()) => true,
+        Err(e) => {
+            log::info!("OS mic mute unavailable: {e}");
+            false
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// The OS mute state of the default input device, or `None` if it can't be
+/// read.
+#[tauri::command]
+pub async fn is_mic_muted() -> Result<Option<bool>, String> {
+    tauri::async_runtime::spawn_blocking(|| platform::is_muted().ok())
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,254 @@
+// Connection health. A background thread checks that the configured server
+// accepts TCP connections and emits `network-status { online, latency_ms }`
+// after every check, so the frontend notices a dropped network before a
+// request times out.
+//
+// Checks run every 30s while online, back off from 5s to 60s while offline,
+// and run every 5s for a short while after the status changes. A jump in
+// wall-clock time versus the monotonic clock (which stops while the machine
+// sleeps) is taken as a resume from sleep and triggers an immediate check.
+//
+// With a proxy configured the server usually can't be reached directly, so
+// the check connects to the proxy instead: that's the hop whose loss takes
+// the app offline.
+//
+// `ping_host` measures round trips to a host (the voice server) on demand,
+// as TCP connect times. It connects directly, never through the configured
+// proxy: a proxy would answer the handshake itself and hide the latency
+// being measured.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+
+use crate::settings::SettingsStore;
+
+const ONLINE_INTERVAL: Duration = Duration::from_secs(30);
+const FAST_INTERVAL: Duration = Duration::from_secs(5);
+/// Checks at `FAST_INTERVAL` after a status change
+const FAST_CHECKS: u32 = 3;
+const OFFLINE_MIN_INTERVAL: Duration = Duration::from_secs(5);
+const OFFLINE_MAX_INTERVAL: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+const PING_GAP: Duration = Duration::from_millis(200);
+const PING_MAX_COUNT: u32 = 20;
+/// How often the wait wakes to look for a resume from sleep
+const SLEEP_TICK: Duration = Duration::from_secs(2);
+const SLEEP_JUMP: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct MonitorConfig {
+    /// `host:port` to probe; no checks while unset
+    target: Option<String>,
+    recheck: bool,
+}
+
+/// Shared between the commands and the monitor thread, which the condvar
+/// wakes for a new target or an explicit recheck.
+pub struct NetworkMonitor {
+    config: Mutex<MonitorConfig>,
+    changed: Condvar,
+}
+
+#[derive(Clone, Serialize)]
+struct NetworkStatus {
+    online: bool,
+    latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PingResult {
+    /// `None` when every attempt was lost
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub loss_percent: f64,
+}
+
+fn ping(host: &str, port: u16, count: u32) -> Result<PingResult, String> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("couldn't resolve {host}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("no address for {host}"))?;
+    let mut times = Vec::new();
+    for attempt in 0..count {
+        if attempt > 0 {
+            std::thread::sleep(PING_GAP);
+        }
+        let start = Instant::now();
+        if TcpStream::connect_timeout(&addr, PING_TIMEOUT).is_ok() {
+            times.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+    let lost = count - times.len() as u32;
+    Ok(PingResult {
+        min_ms: times.iter().copied().reduce(f64::min),
+        avg_ms: (!times.is_empty()).then(|| times.iter().sum::<f64>() / times.len() as f64),
+        max_ms: times.iter().copied().reduce(f64::max),
+        loss_percent: lost as f64 / count as f64 * 100.0,
+    })
+}
+
+fn target_for(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    Some(format!("{}:{}", url.host_str()?, url.port_or_known_default()?))
+}
+
+/// Connect time to the first address that accepts, if any does.
+fn probe(target: &str) -> Option<Duration> {
+    let addrs = target.to_socket_addrs().ok()?;
+    addrs.into_iter().find_map(|addr| {
+        let start = Instant::now();
+        TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .ok()
+            .map(|_| start.elapsed())
+    })
+}
+
+/// Wait up to `interval`, returning early for a recheck request or when the
+/// machine appears to have slept.
+fn wait(monitor: &NetworkMonitor, interval: Duration) {
+    let deadline = Instant::now() + interval;
+    let mut config = monitor.config.lock().unwrap();
+    loop {
+        let now = Instant::now();
+        if config.recheck || now >= deadline {
+            config.recheck = false;
+            return;
+        }
+        let wall = SystemTime::now();
+        config = monitor
+            .changed
+            .wait_timeout(config, SLEEP_TICK.min(deadline - now))
+            .unwrap()
+            .0;
+        let wall_elapsed = wall.elapsed().unwrap_or_default();
+        if wall_elapsed > now.elapsed() + SLEEP_JUMP {
+            log::info!("resumed from sleep, rechecking network");
+            return;
+        }
+    }
+}
+
+fn monitor_loop<R: Runtime>(app: AppHandle<R>, monitor: Arc<NetworkMonitor>) {
+    let mut online: Option<bool> = None;
+    let mut fast_checks = 0;
+    let mut offline_interval = OFFLINE_MIN_INTERVAL;
+    loop {
+        let target = {
+            let config = monitor.config.lock().unwrap();
+            // Park until a server is configured
+            let mut config = monitor
+                .changed
+                .wait_while(config, |c| c.target.is_none())
+                .unwrap();
+            config.recheck = false;
+            config.target.clone().unwrap_or_default()
+        };
+
+        let proxy = crate::proxy::current(&app)
+            .https_url()
+            .and_then(|url| target_for(url.as_str()));
+        let latency = probe(proxy.as_deref().unwrap_or(&target));
+        let is_online = latency.is_some();
+        if online != Some(is_online) {
+            if online.is_some() {
+                log::info!("network is {}", if is_online { "back" } else { "down" });
+            }
+            online = Some(is_online);
+            crate::tray::set_connection_status(&app, is_online);
+            fast_checks = FAST_CHECKS;
+            offline_interval = OFFLINE_MIN_INTERVAL;
+        }
+        let _ = app.emit(
+            "network-status",
+            NetworkStatus {
+                online: is_online,
+                latency_ms: latency.map(|d| d.as_millis() as u64),
+            },
+        );
+
+        let interval = if fast_checks > 0 {
+            fast_checks -= 1;
+            FAST_INTERVAL
+        } else if is_online {
+            ONLINE_INTERVAL
+        } else {
+            let interval = offline_interval;
+            offline_interval = (offline_interval * 2).min(OFFLINE_MAX_INTERVAL);
+            interval
+        };
+        wait(&monitor, interval);
+    }
+}
+
+/// Manage the monitor state, so the commands work before `start`.
+pub fn init(app: &tauri::App) {
+    let target = app
+        .state::<SettingsStore>()
+        .get()
+        .server_url
+        .as_deref()
+        .and_then(target_for);
+    let monitor = Arc::new(NetworkMonitor {
+        config: Mutex::new(MonitorConfig {
+            target,
+            recheck: false,
+        }),
+        changed: Condvar::new(),
+    });
+    app.manage(monitor);
+}
+
+/// Start monitoring. Started by `deferred_init`.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let monitor = app.state::<Arc<NetworkMonitor>>().inner().clone();
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("network-monitor".into())
+        .spawn(move || monitor_loop(handle, monitor))
+    {
+        log::warn!("failed to start network monitor: {e}");
+    }
+}
+
+/// Set the server whose reachability is monitored (`None` stops checking).
+#[tauri::command]
+pub fn set_server_url(app: AppHandle, url: Option<String>) -> Result<(), String> {
+    let target = match url.as_deref() {
+        Some(url) => Some(target_for(url).ok_or_else(|| format!("invalid server URL \"{url}\""))?),
+        None => None,
+    };
+    let monitor = app.state::<Arc<NetworkMonitor>>();
+    {
+        let mut config = monitor.config.lock().unwrap();
+        config.target = target;
+        config.recheck = true;
+    }
+    monitor.changed.notify_all();
+    app.state::<SettingsStore>().update(&app, |s| s.server_url = url);
+    Ok(())
+}
+
+/// Check reachability now instead of waiting for the next poll.
+#[tauri::command]
+pub fn check_network_now(app: AppHandle) {
+    let monitor = app.state::<Arc<NetworkMonitor>>();
+    monitor.config.lock().unwrap().recheck = true;
+    monitor.changed.notify_all();
+}
+
+/// Time `count` TCP connects (1–20) to `host:port`, each given up on after
+/// two seconds.
+#[tauri::command]
+pub async fn ping_host(host: String, port: u16, count: u32) -> Result<PingResult, String> {
+    let count = count.clamp(1, PING_MAX_COUNT);
+    tauri::async_runtime::spawn_blocking(move || ping(&host, port, count))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,436 @@
+// Message notifications with click-through. tauri-plugin-notification can't
+// report clicks on desktop, so message notifications go through the native
+// APIs directly:
+//
+// - Linux: freedesktop notifications (notify-rust), click via the "default"
+//   action. The spec has no portable inline reply, so no Reply button.
+// - macOS: NSUserNotification (mac-notification-sys) with a Reply field.
+// - Windows: WinRT toasts with a text input and Reply button.
+//
+// A click focuses the main window and emits `notification-clicked`; a reply
+// emits `notification-reply`.
+//
+// Progress notifications (uploads) update in place under the caller's id:
+//
+// - Linux: the notification is replaced by server id, with the progress in
+//   the "value" hint that most notification servers draw as a bar.
+// - Windows: a toast with a data-bound <progress> element, updated by tag.
+// - macOS: NSUserNotification can't be updated, so there's one plain
+//   notification when the progress starts and another when it completes.
+//
+// The permission commands let the frontend spot notifications the OS is
+// blocking and send the user to the right settings page.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::plugin::PermissionState;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+/// Progress notifications currently shown, by the caller's id. Holds the
+/// server's notification id on Linux and the toast's sequence number on
+/// Windows.
+#[derive(Default)]
+pub struct ProgressNotifications(Mutex<HashMap<String, u32>>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageNotification {
+    pub title: String,
+    pub body: String,
+    pub channel_id: String,
+    pub message_id: String,
+}
+
+#[derive(Clone, Serialize)]
+struct NotificationClicked {
+    channel_id: String,
+    message_id: String,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+#[derive(Clone, Serialize)]
+struct NotificationReply {
+    channel_id: String,
+    text: String,
+}
+
+fn on_click<R: Runtime>(app: &AppHandle<R>, n: &MessageNotification) {
+    crate::show_main_window(app);
+    let _ = app.emit(
+        "notification-clicked",
+        NotificationClicked {
+            channel_id: n.channel_id.clone(),
+            message_id: n.message_id.clone(),
+        },
+    );
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn on_reply<R: Runtime>(app: &AppHandle<R>, n: &MessageNotification, text: String) {
+    let _ = app.emit(
+        "notification-reply",
+        NotificationReply {
+            channel_id: n.channel_id.clone(),
+            text,
+        },
+    );
+}
+
+#[cfg(target_os = "linux")]
+fn show_native<R: Runtime>(app: AppHandle<R>, n: MessageNotification) -> Result<(), String> {
+    let handle = notify_rust::Notification::new()
+        .appname("Nexus")
+        .summary(&n.title)
+        .body(&n.body)
+        .action("default", "Open")
+        .show()
+        .map_err(|e| e.to_string())?;
+    // wait_for_action blocks until the notification is clicked or dismissed
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| {
+            if action == "default" {
+                on_click(&app, &n);
+            }
+        });
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn show_native<R: Runtime>(app: AppHandle<R>, n: MessageNotification) -> Result<(), String> {
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+
+    let identifier = app.config().identifier.clone();
+    // send() blocks until the user interacts with the notification
+    std::thread::spawn(move || {
+        let _ = mac_notification_sys::set_application(&identifier);
+        let response = Notification::new()
+            .title(&n.title)
+            .message(&n.body)
+            .main_button(MainButton::Response("Reply"))
+            .wait_for_click(true)
+            .send();
+        match response {
+            Ok(NotificationResponse::Click) => on_click(&app, &n),
+            Ok(NotificationResponse::Reply(text)) => on_reply(&app, &n, text),
+            Ok(_) => {}
+            Err(e) => log::warn!("failed to show notification: {e}"),
+        }
+    });
+    Ok(())
+}
+
+/// AppUserModelID toasts are shown under. Unpackaged dev builds have none
+/// registered; borrow PowerShell's like tauri-plugin-notification does.
+#[cfg(target_os = "windows")]
+fn toast_app_id<R: Runtime>(app: &AppHandle<R>) -> String {
+    if cfg!(debug_assertions) {
+        r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe".to_string()
+    } else {
+        app.config().identifier.clone()
+    }
+}
+
+/// Escape text for the toast XML.
+#[cfg(target_os = "windows")]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(target_os = "windows")]
+fn show_native<R: Runtime>(app: AppHandle<R>, n: MessageNotification) -> Result<(), String> {
+    use windows::core::{IInspectable, Interface, Ref, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::{IPropertyValue, TypedEventHandler};
+    use windows::UI::Notifications::{
+        ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+    };
+
+    let app_id = toast_app_id(&app);
+
+    let xml = format!(
+        r#"<toast activationType="foreground" launch="open">
+  <visual>
+    <binding template="ToastGeneric">
+      <text>{}</text>
+      <text>{}</text>
+    </binding>
+  </visual>
+  <actions>
+    <input id="reply" type="text" placeHolderContent="Reply"/>
+    <action content="Reply" arguments="reply" hint-inputId="reply" activationType="foreground"/>
+  </actions>
+</toast>"#,
+        xml_escape(&n.title),
+        xml_escape(&n.body)
+    );
+
+    let result: windows::core::Result<()> = (|| {
+        let doc = XmlDocument::new()?;
+        doc.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&doc)?;
+        let on_activated = move |_: Ref<'_, ToastNotification>, args: Ref<'_, IInspectable>| {
+            let args: ToastActivatedEventArgs = args.ok()?.cast()?;
+            if args.Arguments()? == "reply" {
+                let text = args
+                    .UserInput()?
+                    .Lookup(&HSTRING::from("reply"))?
+                    .cast::<IPropertyValue>()?
+                    .GetString()?
+                    .to_string();
+                if !text.is_empty() {
+                    on_reply(&app, &n, text);
+                }
+            } else {
+                on_click(&app, &n);
+            }
+            Ok(())
+        };
+        toast.Activated(&TypedEventHandler::new(on_activated))?;
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))?.Show(&toast)
+    })();
+    result.map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn show_progress(previous: Option<u32>, title: &str, progress: f32) -> Result<u32, String> {
+    use notify_rust::{Hint, Notification};
+
+    let mut notification = Notification::new();
+    notification.appname("Nexus").summary(title);
+    if let Some(id) = previous {
+        notification.id(id);
+    }
+    if progress < 1.0 {
+        let percent = (progress * 100.0).round() as i32;
+        notification
+            .body(&format!("{percent}%"))
+            .hint(Hint::CustomInt("value".into(), percent));
+    } else {
+        notification.body("Complete");
+    }
+    notification
+        .show()
+        .map(|handle| handle.id())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn show_progress(previous: Option<u32>, title: &str, progress: f32) -> Result<u32, String> {
+    let body = match (previous, progress < 1.0) {
+        (None, true) => "Started",
+        (_, false) => "Complete",
+        // Intermediate updates can't be shown
+        (Some(_), true) => return Ok(0),
+    };
+    let title = title.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = mac_notification_sys::Notification::new()
+            .title(&title)
+            .message(body)
+            .send()
+        {
+            log::warn!("failed to show notification: {e}");
+        }
+    });
+    Ok(0)
+}
+
+#[cfg(target_os = "windows")]
+fn show_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    previous: Option<u32>,
+    title: &str,
+    progress: f32,
+) -> Result<u32, String> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{
+        NotificationData, ToastNotification, ToastNotificationManager,
+    };
+
+    const GROUP: &str = "progress";
+    let sequence = previous.map_or(1, |s| s + 1);
+    let status = if progress < 1.0 { "Uploading…" } else { "Complete" };
+    let progress = progress.to_string();
+
+    let result: windows::core::Result<()> = (|| {
+        let data = NotificationData::new()?;
+        let values = data.Values()?;
+        values.Insert(&HSTRING::from("progressValue"), &HSTRING::from(progress))?;
+        values.Insert(&HSTRING::from("progressStatus"), &HSTRING::from(status))?;
+        data.SetSequenceNumber(sequence)?;
+        let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(
+            toast_app_id(app),
+        ))?;
+        if previous.is_some() {
+            notifier.UpdateWithTagAndGroup(&data, &HSTRING::from(id), &HSTRING::from(GROUP))?;
+            return Ok(());
+        }
+
+        let escaped = xml_escape(title);
+        let xml = format!(
+            r#"<toast activationType="foreground" launch="open">
+  <visual>
+    <binding template="ToastGeneric">
+      <text>{escaped}</text>
+      <progress value="{{progressValue}}" status="{{progressStatus}}"/>
+    </binding>
+  </visual>
+</toast>"#
+        );
+        let doc = XmlDocument::new()?;
+        doc.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&doc)?;
+        toast.SetTag(&HSTRING::from(id))?;
+        toast.SetGroup(&HSTRING::from(GROUP))?;
+        toast.SetData(&data)?;
+        notifier.Show(&toast)
+    })();
+    result.map(|_| sequence).map_err(|e| e.to_string())
+}
+
+/// Show or update the progress notification `id`. `progress` runs from 0 to
+/// 1; at 1 the notification switches to "Complete" and `id` is free to be
+/// reused.
+#[tauri::command]
+pub fn show_progress_notification(
+    app: AppHandle,
+    id: String,
+    title: String,
+    progress: f32,
+) -> Result<(), String> {
+    let progress = if progress.is_finite() { progress.clamp(0.0, 1.0) } else { 0.0 };
+    let state = app.state::<ProgressNotifications>();
+    let mut shown = state.0.lock().unwrap();
+    let previous = shown.get(&id).copied();
+    #[cfg(target_os = "windows")]
+    let handle = show_progress(&app, &id, previous, &title, progress)?;
+    #[cfg(not(target_os = "windows"))]
+    let handle = show_progress(previous, &title, progress)?;
+    if progress < 1.0 {
+        shown.insert(id, handle);
+    } else {
+        shown.remove(&id);
+    }
+    Ok(())
+}
+
+/// Show a message notification that navigates to `channel_id` when clicked.
+#[tauri::command]
+pub fn show_message_notification(
+    app: AppHandle,
+    notification: MessageNotification,
+) -> Result<(), String> {
+    show_native(app, notification)
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationPermission {
+    Granted,
+    Denied,
+    /// Not decided yet; `request_notification_permission` will ask
+    Default,
+}
+
+impl From<PermissionState> for NotificationPermission {
+    fn from(state: PermissionState) -> Self {
+        match state {
+            PermissionState::Granted => Self::Granted,
+            PermissionState::Denied => Self::Denied,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// Windows can turn toasts off per app, per user or by group policy, none of
+/// which the plugin reports.
+#[cfg(target_os = "windows")]
+fn native_permission(app: &AppHandle) -> Option<NotificationPermission> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::{NotificationSetting, ToastNotificationManager};
+
+    let app_id = toast_app_id(app);
+    let setting = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))
+        .and_then(|n| n.Setting())
+        .ok()?;
+    Some(if setting == NotificationSetting::Enabled {
+        NotificationPermission::Granted
+    } else {
+        NotificationPermission::Denied
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn native_permission(_app: &AppHandle) -> Option<NotificationPermission> {
+    None
+}
+
+/// Whether the OS will show our notifications.
+#[tauri::command]
+pub fn notification_permission_state(app: AppHandle) -> Result<NotificationPermission, String> {
+    if let Some(permission) = native_permission(&app) {
+        return Ok(permission);
+    }
+    app.notification()
+        .permission_state()
+        .map(Into::into)
+        .map_err(|e| e.to_string())
+}
+
+/// Ask the OS for permission. Only prompts while the state is `default`;
+/// otherwise returns the current state unchanged.
+#[tauri::command]
+pub fn request_notification_permission(app: AppHandle) -> Result<NotificationPermission, String> {
+    match notification_permission_state(app.clone())? {
+        NotificationPermission::Default => app
+            .notification()
+            .request_permission()
+            .map(Into::into)
+            .map_err(|e| e.to_string()),
+        state => Ok(state),
+    }
+}
+
+/// Open the OS notification settings. Linux has no standard page, so try
+/// the GNOME and KDE settings apps in turn.
+#[tauri::command]
+pub fn open_notification_settings(app: AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_plugin_opener::OpenerExt;
+        app.opener()
+            .open_url("x-apple.systempreferences:com.apple.preference.notifications", None::<&str>)
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use tauri_plugin_opener::OpenerExt;
+        app.opener()
+            .open_url("ms-settings:notifications", None::<&str>)
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = app;
+        const CANDIDATES: &[(&str, &[&str])] = &[
+            ("gnome-control-center", &["notifications"]),
+            ("systemsettings", &["kcm_notifications"]),
+            ("systemsettings5", &["kcm_notifications"]),
+        ];
+        CANDIDATES
+            .iter()
+            .find(|(cmd, args)| std::process::Command::new(cmd).args(*args).spawn().is_ok())
+            .map(|_| ())
+            .ok_or_else(|| "no notification settings app found".to_string())
+    }
+}
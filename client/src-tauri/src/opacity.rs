@@ -0,0 +1,91 @@
+// Main window opacity, e.g. a translucent chat overlaid on an editor.
+//
+// - macOS: NSWindow alphaValue.
+// - Windows: a layered window with SetLayeredWindowAttributes. At 1.0 the
+//   layered style is removed again, since layered windows can render with
+//   artifacts in WebView2.
+// - Linux: GTK widget opacity, which only has an effect under a compositing
+//   window manager (all the common Wayland compositors, or X11 with one
+//   running). Without one the window just stays opaque.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+pub const OPACITY_MIN: f64 = 0.3;
+pub const OPACITY_MAX: f64 = 1.0;
+
+#[cfg(target_os = "macos")]
+fn apply_native<R: Runtime>(window: &WebviewWindow<R>, level: f64) -> Result<(), String> {
+    use objc2_app_kit::NSWindow;
+
+    let ptr = window.ns_window().map_err(|e| e.to_string())?;
+    // SAFETY: tauri hands back the window's live NSWindow
+    let ns_window = unsafe { &*(ptr as *const NSWindow) };
+    ns_window.setAlphaValue(level);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn apply_native<R: Runtime>(window: &WebviewWindow<R>, level: f64) -> Result<(), String> {
+    use windows::Win32::Foundation::{COLORREF, HWND};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+        LWA_ALPHA, WS_EX_LAYERED,
+    };
+
+    let hwnd = HWND(window.hwnd().map_err(|e| e.to_string())?.0);
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        if level >= OPACITY_MAX {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style & !(WS_EX_LAYERED.0 as isize));
+            return Ok(());
+        }
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), (level * 255.0).round() as u8, LWA_ALPHA)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply_native<R: Runtime>(window: &WebviewWindow<R>, level: f64) -> Result<(), String> {
+    use gtk::prelude::WidgetExt;
+
+    window
+        .gtk_window()
+        .map_err(|e| e.to_string())?
+        .set_opacity(level);
+    Ok(())
+}
+
+/// Apply the persisted opacity to the main window at startup.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let level = window
+        .app_handle()
+        .state::<SettingsStore>()
+        .get()
+        .window_opacity
+        .clamp(OPACITY_MIN, OPACITY_MAX);
+    // Fully opaque is the native default, nothing to do
+    if level < OPACITY_MAX {
+        if let Err(e) = apply_native(window, level) {
+            log::warn!("failed to restore window opacity: {e}");
+        }
+    }
+}
+
+/// Set the main window's opacity, clamped to 0.3–1.0. Returns the applied
+/// level.
+#[tauri::command]
+pub fn set_window_opacity(app: AppHandle, level: f64) -> Result<f64, String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+    let level = if level.is_nan() {
+        OPACITY_MAX
+    } else {
+        level.clamp(OPACITY_MIN, OPACITY_MAX)
+    };
+    apply_native(&window, level)?;
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.window_opacity = level);
+    Ok(level)
+}
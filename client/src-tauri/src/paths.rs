@@ -0,0 +1,134 @@
+// Where Nexus keeps its files. Normally these are Tauri's per-platform app
+// dirs; a portable install can put everything in one directory instead with
+// `NEXUS_DATA_DIR=<path>` or `--data-dir <path>` (the flag wins). Settings,
+// window state, logs, crash reports, caches and webview storage all move
+// there, except on macOS where WKWebView's storage can't be relocated.
+//
+// The single-instance lock can't follow: tauri-plugin-single-instance uses a
+// D-Bus name (Linux), a named mutex (Windows) or a socket (macOS) keyed by
+// the app identifier rather than a lock file, so a portable copy still
+// counts as a second instance of an installed Nexus running alongside it.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tauri::{Manager, Runtime};
+
+const DATA_DIR_ENV: &str = "NEXUS_DATA_DIR";
+const DATA_DIR_ARG: &str = "--data-dir";
+
+static OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Create `dir` if needed and check a file can be written there.
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".nexus-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(probe)
+}
+
+fn requested(args: &[String]) -> Option<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == DATA_DIR_ARG {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--data-dir=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Pick up the override. Must run at the very top of `run()`. An unusable
+/// directory falls back to the platform defaults; the returned message
+/// explains why, to be logged once logging is up.
+pub fn init(args: &[String]) -> Option<String> {
+    let dir = requested(args)?;
+    // Relative paths are relative to the executable, which is what a
+    // portable install on a USB stick wants
+    let dir = if dir.is_relative() {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|p| p.join(&dir)))
+            .unwrap_or(dir)
+    } else {
+        dir
+    };
+    if let Err(e) = check_writable(&dir) {
+        return Some(format!(
+            "data dir {} is not writable ({e}); using the default locations",
+            dir.display()
+        ));
+    }
+    let _ = OVERRIDE.set(dir);
+    None
+}
+
+/// The portable data dir, if one is in use.
+pub fn overridden() -> Option<&'static Path> {
+    OVERRIDE.get().map(PathBuf::as_path)
+}
+
+fn resolve<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    sub: Option<&str>,
+    default: impl FnOnce(&tauri::path::PathResolver<R>) -> tauri::Result<PathBuf>,
+) -> tauri::Result<PathBuf> {
+    match (overridden(), sub) {
+        (Some(root), Some(sub)) => Ok(root.join(sub)),
+        (Some(root), None) => Ok(root.to_path_buf()),
+        (None, _) => default(manager.path()),
+    }
+}
+
+/// Replacement for `app_config_dir()`.
+pub fn config_dir<R: Runtime, M: Manager<R>>(manager: &M) -> tauri::Result<PathBuf> {
+    resolve(manager, None, |p| p.app_config_dir())
+}
+
+/// Replacement for `app_data_dir()`.
+pub fn data_dir<R: Runtime, M: Manager<R>>(manager: &M) -> tauri::Result<PathBuf> {
+    resolve(manager, None, |p| p.app_data_dir())
+}
+
+/// Replacement for `app_local_data_dir()`.
+pub fn local_data_dir<R: Runtime, M: Manager<R>>(manager: &M) -> tauri::Result<PathBuf> {
+    resolve(manager, None, |p| p.app_local_data_dir())
+}
+
+/// Replacement for `app_cache_dir()`.
+pub fn cache_dir<R: Runtime, M: Manager<R>>(manager: &M) -> tauri::Result<PathBuf> {
+    resolve(manager, Some("cache"), |p| p.app_cache_dir())
+}
+
+/// Replacement for `app_log_dir()`.
+pub fn log_dir<R: Runtime, M: Manager<R>>(manager: &M) -> tauri::Result<PathBuf> {
+    resolve(manager, Some("logs"), |p| p.app_log_dir())
+}
+
+/// `config_dir()` for use before the app is built. Mirrors
+/// `app_config_dir()`: `<config dir>/<identifier>`.
+pub fn early_config_dir(identifier: &str) -> Option<PathBuf> {
+    match overridden() {
+        Some(root) => Some(root.to_path_buf()),
+        None => dirs::config_dir().map(|d| d.join(identifier)),
+    }
+}
+
+/// `log_dir()` for use before the app is built.
+pub fn early_log_dir(identifier: &str) -> Option<PathBuf> {
+    if let Some(root) = overridden() {
+        return Some(root.join("logs"));
+    }
+    #[cfg(target_os = "macos")]
+    {
+        dirs::home_dir().map(|h| h.join("Library/Logs").join(identifier))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        dirs::data_local_dir().map(|d| d.join(identifier).join("logs"))
+    }
+}
@@ -0,0 +1,287 @@
+// Which webview permission requests are granted, per kind of permission:
+// allowed, denied, or asked about. Policies are saved in settings.
+//
+// For "ask", the request is held and `permission-request { label, kind }` is
+// emitted; the frontend answers with `respond_permission(label, kind,
+// allow)`, which only settles that window's requests. A request covering
+// several kinds (camera and microphone together) is only granted once every
+// kind it needs is allowed, and is denied as soon as one is denied. Requests
+// still waiting when their page goes away, by navigation or by the window
+// closing, are denied.
+//
+// Only enforced on Linux, where WebKitGTK's permission-request signal is
+// handled here. WebView2 and WKWebView are left to `grant_webview_permissions`
+// and the OS's own prompts.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    Microphone,
+    Camera,
+    ScreenCapture,
+    Geolocation,
+    Notifications,
+    /// Device enumeration, pointer lock, clipboard and the like
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionPolicy {
+    Allow,
+    Deny,
+    Ask,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionPolicies {
+    pub microphone: PermissionPolicy,
+    pub camera: PermissionPolicy,
+    pub screen_capture: PermissionPolicy,
+    pub geolocation: PermissionPolicy,
+    pub notifications: PermissionPolicy,
+    pub other: PermissionPolicy,
+}
+
+impl Default for PermissionPolicies {
+    fn default() -> Self {
+        Self {
+            microphone: PermissionPolicy::Ask,
+            camera: PermissionPolicy::Ask,
+            screen_capture: PermissionPolicy::Ask,
+            geolocation: PermissionPolicy::Ask,
+            notifications: PermissionPolicy::Allow,
+            other: PermissionPolicy::Allow,
+        }
+    }
+}
+
+impl PermissionPolicies {
+    #[cfg(target_os = "linux")]
+    pub fn get(&self, kind: PermissionKind) -> PermissionPolicy {
+        match kind {
+            PermissionKind::Microphone => self.microphone,
+            PermissionKind::Camera => self.camera,
+            PermissionKind::ScreenCapture => self.screen_capture,
+            PermissionKind::Geolocation => self.geolocation,
+            PermissionKind::Notifications => self.notifications,
+            PermissionKind::Other => self.other,
+        }
+    }
+
+    fn set(&mut self, kind: PermissionKind, policy: PermissionPolicy) {
+        let slot = match kind {
+            PermissionKind::Microphone => &mut self.microphone,
+            PermissionKind::Camera => &mut self.camera,
+            PermissionKind::ScreenCapture => &mut self.screen_capture,
+            PermissionKind::Geolocation => &mut self.geolocation,
+            PermissionKind::Notifications => &mut self.notifications,
+            PermissionKind::Other => &mut self.other,
+        };
+        *slot = policy;
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    use gtk::glib::translate::ToGlibPtr;
+    use gtk::glib::{Cast, ObjectExt};
+    use gtk::prelude::WidgetExt;
+    use serde::Serialize;
+    use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+    use webkit2gtk::{
+        GeolocationPermissionRequest, LoadEvent, NotificationPermissionRequest,
+        PermissionRequest, PermissionRequestExt, UserMediaPermissionRequest,
+        UserMediaPermissionRequestExt, WebViewExt,
+    };
+
+    use super::{PermissionKind, PermissionPolicy};
+    use crate::settings::SettingsStore;
+
+    /// A request from window `label` waiting on answers for `waiting`.
+    struct Pending {
+        label: String,
+        request: PermissionRequest,
+        waiting: HashSet<PermissionKind>,
+    }
+
+    thread_local! {
+        // WebKit objects live on the GTK main thread, and so does this
+        static PENDING: RefCell<Vec<Pending>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Clone, Serialize)]
+    struct PermissionRequested {
+        label: String,
+        kind: PermissionKind,
+    }
+
+    fn kinds(request: &PermissionRequest) -> Vec<PermissionKind> {
+        if let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() {
+            // No binding for this one yet
+            let display = unsafe {
+                webkit2gtk::ffi::webkit_user_media_permission_is_for_display_device(
+                    media.to_glib_none().0,
+                )
+            };
+            if display != 0 {
+                return vec![PermissionKind::ScreenCapture];
+            }
+            let mut kinds = Vec::new();
+            if media.is_for_audio_device() {
+                kinds.push(PermissionKind::Microphone);
+            }
+            if media.is_for_video_device() {
+                kinds.push(PermissionKind::Camera);
+            }
+            return kinds;
+        }
+        if request.is::<GeolocationPermissionRequest>() {
+            return vec![PermissionKind::Geolocation];
+        }
+        if request.is::<NotificationPermissionRequest>() {
+            return vec![PermissionKind::Notifications];
+        }
+        vec![PermissionKind::Other]
+    }
+
+    fn decide<R: Runtime>(app: &AppHandle<R>, label: &str, request: &PermissionRequest) {
+        let policies = app.state::<SettingsStore>().get().permissions;
+        let mut waiting = HashSet::new();
+        for kind in kinds(request) {
+            match policies.get(kind) {
+                PermissionPolicy::Allow => {}
+                PermissionPolicy::Deny => {
+                    log::info!("denied webview {kind:?} permission by policy");
+                    request.deny();
+                    return;
+                }
+                PermissionPolicy::Ask => {
+                    waiting.insert(kind);
+                }
+            }
+        }
+        if waiting.is_empty() {
+            request.allow();
+            return;
+        }
+        for &kind in &waiting {
+            let _ = app.emit(
+                "permission-request",
+                PermissionRequested {
+                    label: label.to_string(),
+                    kind,
+                },
+            );
+        }
+        PENDING.with_borrow_mut(|pending| {
+            pending.push(Pending {
+                label: label.to_string(),
+                request: request.clone(),
+                waiting,
+            })
+        });
+    }
+
+    /// Apply an answer to every request from window `label` waiting on
+    /// `kind`. Main thread only.
+    pub fn settle(label: &str, kind: PermissionKind, allow: bool) {
+        PENDING.with_borrow_mut(|pending| {
+            pending.retain_mut(|p| {
+                if p.label != label || !p.waiting.remove(&kind) {
+                    return true;
+                }
+                if !allow {
+                    p.request.deny();
+                    return false;
+                }
+                if p.waiting.is_empty() {
+                    p.request.allow();
+                    return false;
+                }
+                true
+            });
+        });
+    }
+
+    /// Deny and forget the requests from window `label`, whose page they
+    /// came from is gone.
+    fn discard(label: &str) {
+        PENDING.with_borrow_mut(|pending| {
+            pending.retain(|p| {
+                if p.label != label {
+                    return true;
+                }
+                p.request.deny();
+                false
+            });
+        });
+    }
+
+    pub fn init<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+        let app = window.app_handle().clone();
+        let label = window.label().to_string();
+        window.with_webview(move |webview| {
+            let webview = webview.inner();
+            let request_label = label.clone();
+            webview.connect_permission_request(move |_wv, request| {
+                decide(&app, &request_label, request);
+                true
+            });
+            let load_label = label.clone();
+            webview.connect_load_changed(move |_wv, event| {
+                if event == LoadEvent::Started {
+                    discard(&load_label);
+                }
+            });
+            webview.connect_destroy(move |_wv| discard(&label));
+        })
+    }
+}
+
+/// Handle the window's webview permission requests by policy. Call once per
+/// window.
+#[cfg(target_os = "linux")]
+pub use platform::init;
+
+/// The saved policy for each kind of permission.
+#[tauri::command]
+pub fn get_permission_policies(app: AppHandle) -> PermissionPolicies {
+    app.state::<SettingsStore>().get().permissions
+}
+
+/// Allow, deny or ask for `kind` from now on. Requests already waiting for an
+/// answer keep waiting.
+#[tauri::command]
+pub fn set_permission_policy(app: AppHandle, kind: PermissionKind, policy: PermissionPolicy) {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.permissions.set(kind, policy));
+}
+
+/// Answer a `permission-request` from window `label` for `kind`.
+#[tauri::command]
+pub fn respond_permission(
+    app: AppHandle,
+    label: String,
+    kind: PermissionKind,
+    allow: bool,
+) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    return app
+        .run_on_main_thread(move || platform::settle(&label, kind, allow))
+        .map_err(|e| e.to_string());
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (app, label, kind, allow);
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+// Picture-in-picture: a small borderless, always-on-top window showing the
+// active speaker's video. It loads the SPA's `/pip` route; the stream to show
+// is pushed to it with `pip-stream-changed` (and can be pulled with
+// `get_pip_stream` once its listeners are attached). Its geometry is
+// remembered separately from the main window's.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::window_state;
+
+pub const PIP_LABEL: &str = "pip";
+
+/// The video stream selection most recently sent to the PiP window.
+#[derive(Default)]
+pub struct PipState(Mutex<Option<serde_json::Value>>);
+
+fn set_stream(app: &AppHandle, stream: serde_json::Value) {
+    *app.state::<PipState>().0.lock().unwrap() = Some(stream.clone());
+    let _ = app.emit_to(PIP_LABEL, "pip-stream-changed", stream);
+}
+
+#[tauri::command]
+pub fn open_pip_window(app: AppHandle, stream: Option<serde_json::Value>) -> Result<(), String> {
+    if let Some(stream) = stream {
+        set_stream(&app, stream);
+    }
+    if let Some(window) = app.get_webview_window(PIP_LABEL) {
+        let _ = window.show();
+        crate::window_events::refresh(&app);
+        return Ok(());
+    }
+
+    let builder = WebviewWindowBuilder::new(&app, PIP_LABEL, WebviewUrl::App("pip".into()))
+        .title("Nexus — Picture in Picture")
+        .inner_size(400.0, 225.0)
+        .min_inner_size(240.0, 135.0)
+        .max_inner_size(960.0, 540.0)
+        .resizable(true)
+        .decorations(false)
+        .always_on_top(true)
+        .theme(crate::theme::forced(&app))
+        .skip_taskbar(true)
+        .devtools(crate::devtools::enabled())
+        .visible(false)
+        .on_navigation(crate::external_links::on_navigation(&app))
+        .on_new_window(crate::external_links::on_new_window(&app));
+    let proxy = crate::proxy::at_launch(&app);
+    let window = crate::with_webview_environment(&app, builder, None, &proxy)
+        .and_then(|builder| builder.build())
+        .map_err(|e| e.to_string())?;
+    #[cfg(target_os = "linux")]
+    crate::user_agent::apply(&window);
+    window_state::restore(&window);
+    window_state::track(&window);
+    crate::zoom::init(&window);
+    crate::media_keys::track(&window);
+    crate::window_events::track(&window);
+    window.show().map_err(|e| e.to_string())?;
+    crate::window_events::refresh(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn close_pip_window(app: AppHandle) -> Result<(), String> {
+    match app.get_webview_window(PIP_LABEL) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Change which video stream the PiP window shows.
+#[tauri::command]
+pub fn set_pip_stream(app: AppHandle, stream: serde_json::Value) {
+    set_stream(&app, stream);
+}
+
+#[tauri::command]
+pub fn get_pip_stream(app: AppHandle) -> Option<serde_json::Value> {
+    app.state::<PipState>().0.lock().unwrap().clone()
+}
@@ -0,0 +1,110 @@
+// AC/battery state for the frontend's low-power mode. A background thread
+// polls the batteries and emits `power-status-changed` with the full status
+// whenever the machine switches between mains and battery. Desktops without
+// a battery simply report `on_battery: false`.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use starship_battery::units::ratio::percent;
+use starship_battery::State;
+use tauri::{AppHandle, Emitter, Runtime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    /// Combined charge of all batteries
+    pub battery_percent: Option<f32>,
+    pub charging: Option<bool>,
+}
+
+const NO_BATTERY: PowerStatus = PowerStatus {
+    on_battery: false,
+    battery_percent: None,
+    charging: None,
+};
+
+fn read(manager: &starship_battery::Manager) -> Result<PowerStatus, String> {
+    let batteries: Vec<_> = manager
+        .batteries()
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .collect();
+    if batteries.is_empty() {
+        return Ok(NO_BATTERY);
+    }
+    // Weight by capacity so a nearly empty small battery doesn't skew it
+    let energy: f32 = batteries.iter().map(|b| b.energy().value).sum();
+    let full: f32 = batteries.iter().map(|b| b.energy_full().value).sum();
+    let battery_percent = if full > 0.0 {
+        (energy / full * 100.0).clamp(0.0, 100.0)
+    } else {
+        let total: f32 = batteries
+            .iter()
+            .map(|b| b.state_of_charge().get::<percent>())
+            .sum();
+        total / batteries.len() as f32
+    };
+    let on_battery = batteries
+        .iter()
+        .any(|b| matches!(b.state(), State::Discharging | State::Empty));
+    let charging = batteries.iter().any(|b| b.state() == State::Charging);
+    Ok(PowerStatus {
+        on_battery,
+        battery_percent: Some(battery_percent),
+        charging: Some(charging),
+    })
+}
+
+fn status() -> PowerStatus {
+    let manager = starship_battery::Manager::new().map_err(|e| e.to_string());
+    match manager.and_then(|m| read(&m)) {
+        Ok(status) => status,
+        Err(e) => {
+            log::debug!("battery status unavailable: {e}");
+            NO_BATTERY
+        }
+    }
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    let manager = match starship_battery::Manager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            log::info!("battery status unavailable, not watching it: {e}");
+            return;
+        }
+    };
+    let mut last = read(&manager).unwrap_or(NO_BATTERY);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Ok(current) = read(&manager) else {
+            continue;
+        };
+        if current.on_battery != last.on_battery {
+            log::info!("power source changed: on battery = {}", current.on_battery);
+            let _ = app.emit("power-status-changed", current);
+        }
+        last = current;
+    }
+}
+
+/// Start watching the power source. Started by `deferred_init`.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("power-monitor".into())
+        .spawn(move || poll_loop(handle))
+    {
+        log::warn!("failed to start power monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn get_power_status() -> Result<PowerStatus, String> {
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,61 @@
+// Memory / CPU usage of the Nexus process for the diagnostics panel.
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStats {
+    pub memory_bytes: u64,
+    /// Percent of a single core, so it can exceed 100 on multi-core machines.
+    pub cpu_percent: f32,
+    pub uptime_secs: u64,
+}
+
+fn measure(include_children: bool) -> Result<ProcessStats, String> {
+    let pid = sysinfo::get_current_pid().map_err(|e| e.to_string())?;
+    let kind = ProcessRefreshKind::nothing().with_memory().with_cpu();
+
+    // CPU usage is a delta between two refreshes, so take a fresh snapshot
+    // now and another after the minimum interval — a single refresh always
+    // reports 0%.
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+
+    let process = sys.process(pid).ok_or("current process not found")?;
+    let mut stats = ProcessStats {
+        memory_bytes: process.memory(),
+        cpu_percent: process.cpu_usage(),
+        uptime_secs: process.run_time(),
+    };
+
+    // Webview helpers (WebKitWebProcess on Linux, msedgewebview2 on Windows)
+    // are child processes. WKWebView's XPC helpers on macOS are owned by
+    // launchd, so they aren't counted there.
+    if include_children {
+        let children: Vec<Pid> = sys
+            .processes()
+            .values()
+            .filter(|p| p.parent() == Some(pid))
+            .map(|p| p.pid())
+            .collect();
+        for child in children {
+            if let Some(p) = sys.process(child) {
+                stats.memory_bytes += p.memory();
+                stats.cpu_percent += p.cpu_usage();
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// Current memory and CPU usage. Pass `include_children` to add in the
+/// webview helper processes.
+#[tauri::command]
+pub async fn get_process_stats(include_children: Option<bool>) -> Result<ProcessStats, String> {
+    let include_children = include_children.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || measure(include_children))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,154 @@
+// Whether Nexus is the default handler for its URL schemes, and making it
+// so, e.g. after another build or app took `nexus://` over.
+//
+// - Linux: xdg-mime's default for `x-scheme-handler/<scheme>`, through the
+//   deep-link plugin, which also writes the .desktop entry.
+// - Windows: the HKCU `Software\Classes\<scheme>` open command, through the
+//   deep-link plugin.
+// - macOS: Launch Services' default handler (LSCopyDefaultHandlerForURLScheme
+//   / LSSetDefaultHandlerForURLScheme), compared by bundle identifier.
+
+use tauri::AppHandle;
+
+/// Schemes these commands accept, so they can't claim http or mailto.
+const HANDLED_SCHEMES: &[&str] = &[crate::deep_link::SCHEME];
+
+fn check_scheme(scheme: &str) -> Result<(), String> {
+    if HANDLED_SCHEMES.contains(&scheme) {
+        Ok(())
+    } else {
+        Err(format!("Nexus doesn't handle {scheme}:// links"))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+
+    use tauri::{AppHandle, Runtime};
+
+    type CFStringRef = *const c_void;
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: *const c_void,
+            s: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFStringGetCString(
+            s: CFStringRef,
+            buffer: *mut c_char,
+            size: isize,
+            encoding: u32,
+        ) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn LSCopyDefaultHandlerForURLScheme(scheme: CFStringRef) -> CFStringRef;
+        fn LSSetDefaultHandlerForURLScheme(scheme: CFStringRef, handler: CFStringRef) -> i32;
+    }
+
+    /// An owned CFString, released on drop.
+    struct CfString(CFStringRef);
+
+    impl CfString {
+        fn new(s: &str) -> Result<Self, String> {
+            let s = CString::new(s).map_err(|e| e.to_string())?;
+            let cf = unsafe {
+                CFStringCreateWithCString(std::ptr::null(), s.as_ptr(), K_CF_STRING_ENCODING_UTF8)
+            };
+            if cf.is_null() {
+                Err("failed to create CFString".into())
+            } else {
+                Ok(Self(cf))
+            }
+        }
+
+        fn read(&self) -> Option<String> {
+            // Bundle identifiers are short; anything longer isn't ours anyway
+            let mut buf = [0 as c_char; 256];
+            let ok = unsafe {
+                CFStringGetCString(
+                    self.0,
+                    buf.as_mut_ptr(),
+                    buf.len() as isize,
+                    K_CF_STRING_ENCODING_UTF8,
+                )
+            };
+            if ok == 0 {
+                return None;
+            }
+            let s = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+            Some(s.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for CfString {
+        fn drop(&mut self) {
+            unsafe { CFRelease(self.0) };
+        }
+    }
+
+    pub fn is_default<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> Result<bool, String> {
+        let scheme = CfString::new(scheme)?;
+        let handler = unsafe { LSCopyDefaultHandlerForURLScheme(scheme.0) };
+        if handler.is_null() {
+            return Ok(false);
+        }
+        let handler = CfString(handler);
+        Ok(handler
+            .read()
+            .is_some_and(|id| id.eq_ignore_ascii_case(&app.config().identifier)))
+    }
+
+    pub fn set_default<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> Result<(), String> {
+        let scheme = CfString::new(scheme)?;
+        let bundle_id = CfString::new(&app.config().identifier)?;
+        let status = unsafe { LSSetDefaultHandlerForURLScheme(scheme.0, bundle_id.0) };
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(format!("LSSetDefaultHandlerForURLScheme failed (OSStatus {status})"))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use tauri::{AppHandle, Runtime};
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    pub fn is_default<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> Result<bool, String> {
+        app.deep_link().is_registered(scheme).map_err(|e| e.to_string())
+    }
+
+    pub fn set_default<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> Result<(), String> {
+        app.deep_link().register(scheme).map_err(|e| e.to_string())
+    }
+}
+
+/// Whether links with `scheme` open in this Nexus.
+#[tauri::command]
+pub async fn is_default_protocol_handler(app: AppHandle, scheme: String) -> Result<bool, String> {
+    check_scheme(&scheme)?;
+    tauri::async_runtime::spawn_blocking(move || platform::is_default(&app, &scheme))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Make this Nexus the handler for `scheme` links.
+#[tauri::command]
+pub async fn set_as_default_protocol_handler(app: AppHandle, scheme: String) -> Result<(), String> {
+    check_scheme(&scheme)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        platform::set_default(&app, &scheme)?;
+        log::info!("registered as the default handler for {scheme}://");
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,143 @@
+// Outbound proxy configuration, shared by the webview and the updater's
+// HTTP client.
+//
+// - "system" uses the OS proxy: the HTTP(S)_PROXY / NO_PROXY env vars for
+//   the updater, and the platform's own settings for the webview.
+// - "manual" uses the configured URLs everywhere.
+// - "none" connects directly.
+//
+// WebKitGTK picks up changes at runtime. WebView2 only takes a proxy at
+// creation (`--proxy-server`), so on Windows a change applies to the webview
+// after a restart; WKWebView always follows the macOS system settings.
+// The updater is rebuilt for every check, so it's never stale.
+//
+// Account windows can override the proxy for their own webview (see
+// `accounts::set_account_proxy`); the global setting still applies to the
+// main window, the updater and every account without an override.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, Url, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    #[default]
+    System,
+    Manual,
+    None,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|n| std::env::var(n).ok())
+        .filter(|v| !v.is_empty())
+}
+
+impl ProxyConfig {
+    /// The proxy to use for HTTPS requests (all of ours are HTTPS), if any.
+    pub fn https_url(&self) -> Option<Url> {
+        let raw = match self.mode {
+            ProxyMode::None => None,
+            ProxyMode::Manual => self.https.clone().or_else(|| self.http.clone()),
+            ProxyMode::System => env_var(&["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]),
+        }?;
+        Url::parse(&raw).ok()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for url in [&self.http, &self.https].into_iter().flatten() {
+            Url::parse(url).map_err(|e| format!("invalid proxy URL \"{url}\": {e}"))?;
+        }
+        if self.mode == ProxyMode::Manual && self.http.is_none() && self.https.is_none() {
+            return Err("manual proxy mode needs an http or https proxy URL".into());
+        }
+        Ok(())
+    }
+}
+
+pub fn current<R: Runtime, M: Manager<R>>(manager: &M) -> ProxyConfig {
+    manager.state::<SettingsStore>().get().proxy
+}
+
+/// The global proxy as it was when the first webview started. Later webviews
+/// sharing its WebView2 environment have to be started with the same one.
+pub fn at_launch<R: Runtime, M: Manager<R>>(manager: &M) -> ProxyConfig {
+    static AT_LAUNCH: OnceLock<ProxyConfig> = OnceLock::new();
+    AT_LAUNCH.get_or_init(|| current(manager)).clone()
+}
+
+/// Apply the proxy to a webview where that's possible at runtime.
+pub fn apply_to_webview<R: Runtime>(window: &WebviewWindow<R>, config: &ProxyConfig) {
+    #[cfg(target_os = "linux")]
+    {
+        let config = config.clone();
+        let _ = window.with_webview(move |webview| {
+            use webkit2gtk::{NetworkProxyMode, NetworkProxySettings, WebContextExt, WebViewExt, WebsiteDataManagerExt};
+            let Some(manager) = webview.inner().context().and_then(|c| c.website_data_manager()) else {
+                return;
+            };
+            match config.mode {
+                ProxyMode::System => manager.set_network_proxy_settings(NetworkProxyMode::Default, None),
+                ProxyMode::None => manager.set_network_proxy_settings(NetworkProxyMode::NoProxy, None),
+                ProxyMode::Manual => {
+                    let ignore: Vec<String> = config
+                        .no_proxy
+                        .as_deref()
+                        .unwrap_or_default()
+                        .split(',')
+                        .map(|h| h.trim().to_string())
+                        .filter(|h| !h.is_empty())
+                        .collect();
+                    let ignore: Vec<&str> = ignore.iter().map(String::as_str).collect();
+                    let mut settings = NetworkProxySettings::new(config.http.as_deref(), &ignore);
+                    if let Some(https) = config.https.as_deref() {
+                        settings.add_proxy_for_scheme("https", https);
+                    }
+                    manager.set_network_proxy_settings(NetworkProxyMode::Custom, Some(&mut settings));
+                }
+            }
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (window, config);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyApplied {
+    /// The webview only picks up the change after a restart (Windows).
+    pub requires_restart: bool,
+}
+
+#[tauri::command]
+pub fn get_proxy(app: AppHandle) -> ProxyConfig {
+    current(&app)
+}
+
+#[tauri::command]
+pub fn set_proxy(app: AppHandle, config: ProxyConfig) -> Result<ProxyApplied, String> {
+    config.validate()?;
+    for window in app.webview_windows().values() {
+        if !crate::accounts::has_proxy_override(&app, window.label()) {
+            apply_to_webview(window, &config);
+        }
+    }
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.proxy = config);
+    Ok(ProxyApplied {
+        requires_restart: cfg!(target_os = "windows"),
+    })
+}
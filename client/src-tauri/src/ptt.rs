@@ -0,0 +1,100 @@
+// Global push-to-talk shortcut. Registered with the OS so it works while
+// Nexus is in the background; key down/up are forwarded to the frontend as
+// `ptt-pressed` / `ptt-released`.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::settings::SettingsStore;
+
+#[derive(Default)]
+struct PttInner {
+    shortcut: Option<Shortcut>,
+    pressed: bool,
+}
+
+#[derive(Default)]
+pub struct PttState(Mutex<PttInner>);
+
+/// Global shortcut plugin handler — dispatches events for the PTT binding.
+pub fn handle_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    let Some(state) = app.try_state::<PttState>() else {
+        return;
+    };
+    let mut inner = state.0.lock().unwrap();
+    if inner.shortcut.as_ref() != Some(shortcut) {
+        return;
+    }
+    // Shortcuts are captured at the OS level, so the release arrives even if
+    // the window lost focus mid-press. Track the pressed flag so key repeat
+    // doesn't emit duplicate presses.
+    match event.state() {
+        ShortcutState::Pressed if !inner.pressed => {
+            inner.pressed = true;
+            let _ = app.emit("ptt-pressed", ());
+        }
+        ShortcutState::Released if inner.pressed => {
+            inner.pressed = false;
+            let _ = app.emit("ptt-released", ());
+        }
+        _ => {}
+    }
+}
+
+fn register<R: Runtime>(app: &AppHandle<R>, shortcut: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| e.to_string())?;
+    app.state::<PttState>().0.lock().unwrap().shortcut = Some(shortcut);
+    Ok(())
+}
+
+fn unregister_current<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<PttState>();
+    let mut inner = state.0.lock().unwrap();
+    if let Some(old) = inner.shortcut.take() {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    // Don't leave the frontend transmitting if the binding changes mid-press
+    if inner.pressed {
+        inner.pressed = false;
+        let _ = app.emit("ptt-released", ());
+    }
+}
+
+/// Register the PTT binding from saved settings. Called from `setup`.
+pub fn init(app: &tauri::App) {
+    app.manage(PttState::default());
+    let accelerator = app.state::<SettingsStore>().get().ptt_shortcut;
+    match accelerator.parse::<Shortcut>() {
+        Ok(shortcut) => {
+            if let Err(e) = register(app.handle(), shortcut) {
+                log::warn!("failed to register push-to-talk shortcut {accelerator}: {e}");
+            }
+        }
+        Err(e) => log::warn!("invalid push-to-talk shortcut {accelerator}: {e}"),
+    }
+}
+
+#[tauri::command]
+pub fn set_ptt_shortcut(app: AppHandle, accelerator: String) -> Result<(), String> {
+    let shortcut = accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("invalid shortcut \"{accelerator}\": {e}"))?;
+
+    let previous = app.state::<PttState>().0.lock().unwrap().shortcut;
+    unregister_current(&app);
+    if let Err(e) = register(&app, shortcut) {
+        // Put the old binding back so PTT keeps working
+        if let Some(old) = previous {
+            let _ = register(&app, old);
+        }
+        return Err(e);
+    }
+
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.ptt_shortcut = accelerator);
+    Ok(())
+}
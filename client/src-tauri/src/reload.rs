@@ -0,0 +1,83 @@
+// View → Reload / Hard Reload. A soft reload may serve the page's assets
+// from the webview's memory cache, which after an update can mean a stale
+// frontend; a hard reload revalidates everything with the server:
+//
+// - Linux: WebKitGTK drops its memory cache, then `reload_bypass_cache`.
+// - macOS: WKWebView `reloadFromOrigin`.
+// - Windows: the DevTools protocol's `Page.reload { ignoreCache }`, which is
+//   what Ctrl+Shift+R does in Edge.
+
+use tauri::{Runtime, WebviewWindow};
+
+pub fn soft<R: Runtime>(window: &WebviewWindow<R>) {
+    let _ = window.eval("window.location.reload()");
+}
+
+pub fn hard<R: Runtime>(window: &WebviewWindow<R>) {
+    #[cfg(target_os = "linux")]
+    let result = window.with_webview(|webview| {
+        use gtk::{gio, glib};
+        use webkit2gtk::{WebContextExt, WebViewExt, WebsiteDataManagerExtManual, WebsiteDataTypes};
+
+        let wv = webview.inner();
+        let Some(manager) = wv.context().and_then(|c| c.website_data_manager()) else {
+            wv.reload_bypass_cache();
+            return;
+        };
+        // The callback has to be Send; it runs back on this (the main) thread
+        let wv_for_reload = glib::thread_guard::ThreadGuard::new(wv.clone());
+        manager.clear(
+            WebsiteDataTypes::MEMORY_CACHE,
+            glib::TimeSpan(0),
+            None::<&gio::Cancellable>,
+            move |result| {
+                if let Err(e) = result {
+                    log::warn!("failed to clear webview memory cache: {e}");
+                }
+                wv_for_reload.get_ref().reload_bypass_cache();
+            },
+        );
+    });
+
+    #[cfg(target_os = "macos")]
+    let result = window.with_webview(|webview| {
+        use objc2::runtime::AnyObject;
+
+        // SAFETY: `inner` is the live WKWebView; the returned WKNavigation is
+        // autoreleased and not needed
+        unsafe {
+            let wv = &*(webview.inner() as *const AnyObject);
+            let _: *mut AnyObject = objc2::msg_send![wv, reloadFromOrigin];
+        }
+    });
+
+    #[cfg(target_os = "windows")]
+    let result = window.with_webview(|webview| {
+        unsafe {
+            use webview2_com::CallDevToolsProtocolMethodCompletedHandler;
+            use windows::core::w;
+
+            let core = webview.controller().CoreWebView2().unwrap();
+            let handler = CallDevToolsProtocolMethodCompletedHandler::create(Box::new(
+                |result, _json| {
+                    if let Err(e) = result {
+                        log::warn!("hard reload failed: {e}");
+                    }
+                    Ok(())
+                },
+            ));
+            if let Err(e) = core.CallDevToolsProtocolMethod(
+                w!("Page.reload"),
+                w!(r#"{"ignoreCache":true}"#),
+                &handler,
+            ) {
+                log::warn!("hard reload failed: {e}");
+            }
+        }
+    });
+
+    if let Err(e) = result {
+        log::warn!("hard reload failed, reloading normally: {e}");
+        soft(window);
+    }
+}
@@ -0,0 +1,193 @@
+// Local reminder notifications ("remind me in 15 minutes"). The notification
+// plugin can only schedule on mobile, so each reminder is a timer task here
+// that shows the notification at `deliver_at` and emits
+// `scheduled-notification-fired { id }`.
+//
+// Pending reminders are saved to `reminders.json` in the app data dir and
+// rescheduled on startup; any that came due while the app wasn't running
+// fire right away. Timers check the wall clock in short steps, so a machine
+// that slept through the due time fires on wake instead of late by the
+// length of the sleep.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+const REMINDERS_FILE: &str = "reminders.json";
+/// Longest a timer sleeps before looking at the clock again
+const CLOCK_CHECK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReminderRequest {
+    pub title: String,
+    pub body: String,
+    /// Unix timestamp, in seconds
+    pub deliver_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Reminder {
+    id: String,
+    title: String,
+    body: String,
+    deliver_at: i64,
+}
+
+/// Pending reminders by id.
+#[derive(Default)]
+pub struct Reminders(Mutex<HashMap<String, Reminder>>);
+
+#[derive(Clone, Serialize)]
+struct ReminderFired {
+    id: String,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn new_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("{nanos:x}-{}", COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn reminders_path<R: Runtime, M: Manager<R>>(manager: &M) -> Option<PathBuf> {
+    crate::paths::data_dir(manager)
+        .ok()
+        .map(|d| d.join(REMINDERS_FILE))
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, reminders: &HashMap<String, Reminder>) {
+    let Some(path) = reminders_path(app) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let list: Vec<&Reminder> = reminders.values().collect();
+    match serde_json::to_string_pretty(&list) {
+        Ok(json) => {
+            // Write-then-rename so a crash mid-write can't truncate the file
+            let tmp = path.with_extension("json.tmp");
+            if let Err(e) = std::fs::write(&tmp, json).and_then(|()| std::fs::rename(&tmp, &path)) {
+                log::warn!("failed to save reminders: {e}");
+            }
+        }
+        Err(e) => log::warn!("failed to serialize reminders: {e}"),
+    }
+}
+
+fn fire<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    let state = app.state::<Reminders>();
+    let reminder = {
+        let mut reminders = state.0.lock().unwrap();
+        let Some(reminder) = reminders.remove(id) else {
+            // Cancelled
+            return;
+        };
+        save(app, &reminders);
+        reminder
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(&reminder.title)
+        .body(&reminder.body)
+        .show()
+    {
+        log::warn!("failed to show reminder: {e}");
+    }
+    let _ = app.emit("scheduled-notification-fired", ReminderFired { id: reminder.id });
+}
+
+fn start_timer<R: Runtime>(app: &AppHandle<R>, id: String, deliver_at: i64) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let remaining = deliver_at.saturating_sub(now());
+            if remaining <= 0 {
+                break;
+            }
+            let step = Duration::from_secs(remaining as u64).min(CLOCK_CHECK);
+            tokio::time::sleep(step).await;
+            let cancelled = !app.state::<Reminders>().0.lock().unwrap().contains_key(&id);
+            if cancelled {
+                return;
+            }
+        }
+        fire(&app, &id);
+    });
+}
+
+/// Load saved reminders and schedule them. Called from `setup`.
+pub fn init(app: &tauri::App) {
+    let saved: Vec<Reminder> = reminders_path(app)
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|raw| match serde_json::from_str(&raw) {
+            Ok(list) => Some(list),
+            Err(e) => {
+                log::warn!("reminders file is corrupt, dropping it: {e}");
+                None
+            }
+        })
+        .unwrap_or_default();
+    let reminders: HashMap<String, Reminder> =
+        saved.into_iter().map(|r| (r.id.clone(), r)).collect();
+    let pending: Vec<(String, i64)> = reminders
+        .values()
+        .map(|r| (r.id.clone(), r.deliver_at))
+        .collect();
+    app.manage(Reminders(Mutex::new(reminders)));
+    for (id, deliver_at) in pending {
+        start_timer(app.handle(), id, deliver_at);
+    }
+}
+
+/// Show a notification at `deliver_at`. Returns the reminder's id, for
+/// `cancel_scheduled_notification` and to match the fired event.
+#[tauri::command]
+pub fn schedule_notification(app: AppHandle, payload: ReminderRequest) -> Result<String, String> {
+    if payload.title.trim().is_empty() {
+        return Err("reminder needs a title".into());
+    }
+    let reminder = Reminder {
+        id: new_id(),
+        title: payload.title,
+        body: payload.body,
+        deliver_at: payload.deliver_at,
+    };
+    let id = reminder.id.clone();
+    {
+        let state = app.state::<Reminders>();
+        let mut reminders = state.0.lock().unwrap();
+        reminders.insert(id.clone(), reminder);
+        save(&app, &reminders);
+    }
+    start_timer(&app, id.clone(), payload.deliver_at);
+    Ok(id)
+}
+
+/// Drop a pending reminder. Returns whether it was still pending.
+#[tauri::command]
+pub fn cancel_scheduled_notification(app: AppHandle, id: String) -> bool {
+    let state = app.state::<Reminders>();
+    let mut reminders = state.0.lock().unwrap();
+    let removed = reminders.remove(&id).is_some();
+    if removed {
+        save(&app, &reminders);
+    }
+    removed
+}
@@ -0,0 +1,94 @@
+// Safe mode, for getting out of a UI broken by a theme or plugin. Launching
+// with `--safe-mode` (or relaunching through `relaunch_safe_mode`, which
+// passes `NEXUS_SAFE_MODE=1` to the new process) sets
+// `window.__NEXUS_SAFE_MODE__` so the frontend skips user themes and plugins,
+// and the backend skips injected CSS.
+//
+// A marker file is written at startup and removed on a clean exit. If it's
+// still there on the next launch the previous run crashed or was killed, and
+// the frontend is sent `suggest-safe-mode` once it has loaded.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+const SAFE_MODE_ARG: &str = "--safe-mode";
+const SAFE_MODE_ENV: &str = "NEXUS_SAFE_MODE";
+const MARKER_FILE: &str = ".running";
+
+/// Added to the window builders when safe mode is on.
+pub const INIT_SCRIPT: &str = "window.__NEXUS_SAFE_MODE__ = true;";
+
+static ACTIVE: OnceLock<bool> = OnceLock::new();
+static UNCLEAN_SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+/// Read the flag and env var. Must run in `run()` before any thread starts.
+pub fn init_early(args: &[String]) {
+    let active = args.iter().any(|a| a == SAFE_MODE_ARG)
+        || std::env::var(SAFE_MODE_ENV).is_ok_and(|v| v == "1");
+    // Only this launch: a normal restart from safe mode shouldn't inherit it
+    std::env::remove_var(SAFE_MODE_ENV);
+    let _ = ACTIVE.set(active);
+}
+
+pub fn active() -> bool {
+    ACTIVE.get().copied().unwrap_or(false)
+}
+
+fn marker<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    crate::paths::data_dir(app)
+        .ok()
+        .map(|dir| dir.join(MARKER_FILE))
+}
+
+/// Check for a leftover marker from the last run, then write this run's.
+/// Called from `setup`.
+pub fn init(app: &tauri::App) {
+    let Some(marker) = marker(app.handle()) else {
+        return;
+    };
+    if marker.exists() {
+        log::warn!("previous run didn't shut down cleanly");
+        UNCLEAN_SHUTDOWN.store(true, Ordering::SeqCst);
+    }
+    if active() {
+        log::info!("running in safe mode");
+    }
+    let written = marker
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&marker, std::process::id().to_string()));
+    if let Err(e) = written {
+        log::warn!("failed to write {}: {e}", marker.display());
+    }
+}
+
+/// Remove the marker. Called once a quit has been flushed, before restarts,
+/// and on `RunEvent::Exit`.
+pub fn mark_clean_shutdown<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(marker) = marker(app) {
+        let _ = std::fs::remove_file(marker);
+    }
+}
+
+/// Suggest safe mode if the last run crashed and this one isn't already in
+/// it. Called once the frontend has loaded.
+pub fn announce<R: Runtime>(app: &AppHandle<R>) {
+    if UNCLEAN_SHUTDOWN.load(Ordering::SeqCst) && !active() {
+        let _ = app.emit("suggest-safe-mode", ());
+    }
+}
+
+#[tauri::command]
+pub fn is_safe_mode() -> bool {
+    active()
+}
+
+/// Restart into safe mode, with the usual `before-restart` handshake.
+#[tauri::command]
+pub async fn relaunch_safe_mode(app: AppHandle) -> Result<(), String> {
+    std::env::set_var(SAFE_MODE_ENV, "1");
+    crate::lifecycle::restart_app(app).await
+}
@@ -0,0 +1,64 @@
+// Secrets (the session token) in the OS credential store: Keychain on
+// macOS, Credential Manager on Windows, the Secret Service on Linux. Entries
+// are stored under the app identifier as the service name so they can't
+// collide with other apps' entries.
+//
+// Linux desktops without a Secret Service provider are common, so every
+// failure to reach the store is reported with the `UNAVAILABLE` prefix; the
+// frontend can match on it and fall back to its own storage.
+
+use tauri::AppHandle;
+
+/// Error prefix for "there is no usable credential store on this system".
+const UNAVAILABLE: &str = "secret store unavailable";
+
+fn entry(app: &AppHandle, key: &str) -> Result<keyring::Entry, String> {
+    if key.is_empty() {
+        return Err("secret key is empty".into());
+    }
+    keyring::Entry::new(&app.config().identifier, key).map_err(to_message)
+}
+
+fn to_message(e: keyring::Error) -> String {
+    match e {
+        keyring::Error::PlatformFailure(e) => format!("{UNAVAILABLE}: {e}"),
+        keyring::Error::NoStorageAccess(e) => format!("{UNAVAILABLE}: {e}"),
+        e => e.to_string(),
+    }
+}
+
+/// Run a credential store call off the main thread; the Secret Service is
+/// reached over D-Bus and may prompt to unlock the keyring.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn store_secret(app: AppHandle, key: String, value: String) -> Result<(), String> {
+    blocking(move || entry(&app, &key)?.set_password(&value).map_err(to_message)).await
+}
+
+/// The stored value, or `None` if nothing is stored under `key`.
+#[tauri::command]
+pub async fn get_secret(app: AppHandle, key: String) -> Result<Option<String>, String> {
+    blocking(move || match entry(&app, &key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(to_message(e)),
+    })
+    .await
+}
+
+/// Remove `key`. Deleting a key that isn't stored is not an error.
+#[tauri::command]
+pub async fn delete_secret(app: AppHandle, key: String) -> Result<(), String> {
+    blocking(move || match entry(&app, &key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(to_message(e)),
+    })
+    .await
+}
@@ -0,0 +1,372 @@
+// Persisted user settings, stored as JSON under the app config dir.
+//
+// The file carries a `version`. Older files are upgraded on load by running
+// each step in `MIGRATIONS` on the raw JSON, so renamed or restructured
+// fields survive; fields added since only need a `Default`. Bump
+// `CURRENT_VERSION` together with adding a step.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::permissions::PermissionPolicies;
+use crate::proxy::ProxyConfig;
+use crate::theme::WindowTheme;
+use crate::tray::TrayClickBehavior;
+use crate::updater::UpdateChannel;
+use crate::window_effect::WindowEffect;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+pub const CURRENT_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades a version `n` file to version `n + 1`.
+const MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] = &[
+    // 0 → 1: files from before versioning. The layout is unchanged, the
+    // version field is all that's new.
+    |_| {},
+];
+
+/// Internal bookkeeping the frontend may read but not patch.
+const READ_ONLY_KEYS: &[&str] = &["version", "close_to_tray_notified", "allow_update_downgrade"];
+
+/// Map-valued settings whose keys aren't fixed, so patches may add keys.
+const FREE_FORM_MAPS: &[&str] = &["shortcuts", "injected_css", "account_proxies"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// File format version, see `MIGRATIONS`.
+    pub version: u32,
+    pub ptt_shortcut: String,
+    pub close_to_tray: bool,
+    /// Set once we've shown the "still running in the tray" notification.
+    pub close_to_tray_notified: bool,
+    pub zoom: f64,
+    /// Zoom change per menu step or scroll notch.
+    pub zoom_step: f64,
+    pub zoom_min: f64,
+    pub zoom_max: f64,
+    pub always_on_top: bool,
+    pub idle_detection_enabled: bool,
+    pub idle_threshold_secs: u64,
+    pub update_channel: UpdateChannel,
+    /// Set when the user confirmed switching from beta back to stable, so
+    /// the next update may install an older version.
+    pub allow_update_downgrade: bool,
+    /// Expand dropped folders one level instead of ignoring them.
+    pub drop_expand_directories: bool,
+    /// Menu accelerator overrides, keyed by action id (see `shortcuts::DEFAULTS`).
+    pub shortcuts: HashMap<String, String>,
+    pub spellcheck_languages: Vec<String>,
+    pub proxy: ProxyConfig,
+    /// Per-account overrides of `proxy`, keyed by account id (see `accounts`).
+    pub account_proxies: HashMap<String, ProxyConfig>,
+    /// GPU compositing in the webview. Read before the webview starts, so
+    /// changes apply on the next launch.
+    pub hardware_acceleration: bool,
+    /// Where `submit_crash_report` POSTs reports. Unset disables submission.
+    pub crash_report_endpoint: Option<String>,
+    /// Main window opacity, 0.3–1.0.
+    pub window_opacity: f64,
+    /// Keep the window out of sight when launched at login.
+    pub start_minimized: bool,
+    /// Server whose reachability the network monitor checks.
+    pub server_url: Option<String>,
+    /// Handle the keyboard's media keys.
+    pub media_controls_enabled: bool,
+    /// Keep the media keys while Nexus isn't focused, instead of leaving
+    /// them to other players.
+    pub media_keys_in_background: bool,
+    /// Hide native window decorations in favour of the HTML titlebar.
+    pub frameless: bool,
+    /// Server theme CSS, keyed by window label (see `custom_css`).
+    pub injected_css: HashMap<String, String>,
+    /// off, error, warn, info, debug or trace. `NEXUS_LOG` overrides it.
+    pub log_level: String,
+    /// Menu bar shown in account windows (Windows and Linux).
+    pub menu_visible: bool,
+    /// Output device id (see `audio`); `None` follows the OS default.
+    pub output_device: Option<String>,
+    /// What a left click on the tray icon does.
+    pub tray_click: TrayClickBehavior,
+    /// Forced light/dark window theme; `None` follows the OS.
+    pub window_theme: Option<WindowTheme>,
+    /// Webview permission policies (see `permissions`).
+    pub permissions: PermissionPolicies,
+    /// Main window backdrop (see `window_effect`).
+    pub window_effect: WindowEffect,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            ptt_shortcut: "CmdOrCtrl+Shift+Space".into(),
+            close_to_tray: true,
+            close_to_tray_notified: false,
+            zoom: 1.0,
+            zoom_step: crate::zoom::DEFAULT_ZOOM_STEP,
+            zoom_min: crate::zoom::DEFAULT_ZOOM_MIN,
+            zoom_max: crate::zoom::DEFAULT_ZOOM_MAX,
+            always_on_top: false,
+            idle_detection_enabled: true,
+            idle_threshold_secs: 300,
+            update_channel: UpdateChannel::Stable,
+            allow_update_downgrade: false,
+            drop_expand_directories: false,
+            shortcuts: HashMap::new(),
+            spellcheck_languages: Vec::new(),
+            proxy: ProxyConfig::default(),
+            account_proxies: HashMap::new(),
+            hardware_acceleration: true,
+            crash_report_endpoint: None,
+            window_opacity: 1.0,
+            start_minimized: false,
+            server_url: None,
+            media_controls_enabled: true,
+            media_keys_in_background: false,
+            frameless: false,
+            injected_css: HashMap::new(),
+            log_level: "info".into(),
+            menu_visible: true,
+            output_device: None,
+            tray_click: TrayClickBehavior::default(),
+            window_theme: None,
+            permissions: PermissionPolicies::default(),
+            window_effect: WindowEffect::None,
+        }
+    }
+}
+
+fn settings_path<R: Runtime, M: Manager<R>>(manager: &M) -> Option<PathBuf> {
+    crate::paths::config_dir(manager)
+        .ok()
+        .map(|d| d.join(SETTINGS_FILE))
+}
+
+/// Read settings from disk, falling back to defaults if missing or corrupt.
+pub fn load<R: Runtime, M: Manager<R>>(manager: &M) -> Settings {
+    match settings_path(manager).and_then(|p| read(&p)) {
+        Some(settings) => settings,
+        None => {
+            let mut settings = Settings::default();
+            import_legacy(manager, &mut settings);
+            settings
+        }
+    }
+}
+
+/// Read settings before the Tauri app exists (and so before its path
+/// resolver does), for options that must be applied ahead of the webview
+/// starting.
+pub fn load_early(identifier: &str) -> Settings {
+    crate::paths::early_config_dir(identifier)
+        .map(|d| d.join(SETTINGS_FILE))
+        .and_then(|p| read(&p))
+        .unwrap_or_default()
+}
+
+/// Read the settings file at `path`, if there is one. One that isn't JSON
+/// at all gives the defaults, after being copied to `settings.json.bak`
+/// since the next save replaces it.
+fn read(path: &Path) -> Option<Settings> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    Some(parse(&raw).unwrap_or_else(|| {
+        log::warn!("settings file is corrupt, using defaults");
+        if let Err(e) = std::fs::copy(path, path.with_extension("json.bak")) {
+            log::warn!("failed to back up corrupt settings: {e}");
+        }
+        Settings::default()
+    }))
+}
+
+/// Parse a settings file, migrating it up to `CURRENT_VERSION`. Fields that
+/// don't fit their setting are dropped (so they get the default) one by
+/// one; `None` if the file isn't a JSON object.
+fn parse(raw: &str) -> Option<Settings> {
+    let Ok(serde_json::Value::Object(mut map)) = serde_json::from_str(raw) else {
+        return None;
+    };
+    let version = map
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if version > CURRENT_VERSION {
+        // Written by a newer Nexus; read what we understand
+        log::warn!("settings file version {version} is newer than {CURRENT_VERSION}");
+    }
+    for migrate in MIGRATIONS.iter().skip(version as usize) {
+        migrate(&mut map);
+    }
+    map.insert("version".into(), CURRENT_VERSION.into());
+    if let Ok(settings) = serde_json::from_value(serde_json::Value::Object(map.clone())) {
+        return Some(settings);
+    }
+    // Add the fields back one at a time, keeping those that still parse
+    let mut kept = serde_json::Map::new();
+    let mut settings = Settings::default();
+    for (key, value) in map {
+        kept.insert(key.clone(), value);
+        match serde_json::from_value(serde_json::Value::Object(kept.clone())) {
+            Ok(parsed) => settings = parsed,
+            Err(e) => {
+                log::warn!("ignoring unreadable setting {key}: {e}");
+                kept.remove(&key);
+            }
+        }
+    }
+    Some(settings)
+}
+
+/// Pick up preferences saved as loose files before settings.json existed.
+fn import_legacy<R: Runtime, M: Manager<R>>(manager: &M, settings: &mut Settings) {
+    let Ok(data_dir) = manager.path().app_data_dir() else {
+        return;
+    };
+    if let Ok(s) = std::fs::read_to_string(data_dir.join("minimize_to_tray.txt")) {
+        settings.close_to_tray = s.trim() != "false";
+    }
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, settings: &Settings) {
+    let Some(path) = settings_path(app) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    match serde_json::to_string_pretty(settings) {
+        Ok(json) => {
+            // Write-then-rename so a crash mid-write can't truncate the file
+            let tmp = path.with_extension("json.tmp");
+            if let Err(e) = std::fs::write(&tmp, json).and_then(|()| std::fs::rename(&tmp, &path)) {
+                log::warn!("failed to save settings: {e}");
+            }
+        }
+        Err(e) => log::warn!("failed to serialize settings: {e}"),
+    }
+}
+
+/// Recursively merge `patch` into `target`: objects merge key by key,
+/// anything else replaces. Unknown keys are rejected so a typo can't
+/// silently persist, except in free-form maps where `null` removes an entry.
+fn merge(
+    target: &mut serde_json::Map<String, serde_json::Value>,
+    patch: serde_json::Map<String, serde_json::Value>,
+    path: &str,
+) -> Result<(), String> {
+    let free_form = FREE_FORM_MAPS.contains(&path);
+    for (key, value) in patch {
+        let full = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+        if free_form {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                target.insert(key, value);
+            }
+            continue;
+        }
+        let Some(existing) = target.get_mut(&key) else {
+            return Err(format!("unknown setting \"{full}\""));
+        };
+        match (existing, value) {
+            (serde_json::Value::Object(existing), serde_json::Value::Object(value)) => {
+                merge(existing, value, &full)?
+            }
+            (existing, value) => *existing = value,
+        }
+    }
+    Ok(())
+}
+
+/// Managed wrapper so commands and event handlers share one copy of the
+/// settings and every mutation is written through to disk.
+pub struct SettingsStore(Mutex<Settings>);
+
+impl SettingsStore {
+    pub fn new(settings: Settings) -> Self {
+        Self(Mutex::new(settings))
+    }
+
+    pub fn get(&self) -> Settings {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn update<R: Runtime>(&self, app: &AppHandle<R>, f: impl FnOnce(&mut Settings)) {
+        let mut settings = self.0.lock().unwrap();
+        f(&mut settings);
+        save(app, &settings);
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> Settings {
+    app.state::<SettingsStore>().get()
+}
+
+/// Patch settings with a partial object, e.g. `{ "proxy": { "mode": "none" } }`,
+/// and return the result. This only persists: settings that take effect
+/// immediately (zoom, always on top, shortcuts, autostart, ...) should go
+/// through their own commands so the change is applied too.
+#[tauri::command]
+pub fn update_settings(app: AppHandle, partial: serde_json::Value) -> Result<Settings, String> {
+    let serde_json::Value::Object(patch) = partial else {
+        return Err("settings patch must be an object".into());
+    };
+    if let Some(key) = patch.keys().find(|k| READ_ONLY_KEYS.contains(&k.as_str())) {
+        return Err(format!("setting \"{key}\" is read-only"));
+    }
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.0.lock().unwrap();
+    let serde_json::Value::Object(mut current) =
+        serde_json::to_value(&*settings).map_err(|e| e.to_string())?
+    else {
+        return Err("settings did not serialize to an object".into());
+    };
+    merge(&mut current, patch, "")?;
+    let updated: Settings =
+        serde_json::from_value(serde_json::Value::Object(current)).map_err(|e| e.to_string())?;
+    *settings = updated.clone();
+    save(&app, &settings);
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_migrates_unversioned_file() {
+        let settings = parse(r#"{ "close_to_tray": false, "zoom": 1.25 }"#).unwrap();
+        assert_eq!(settings.version, CURRENT_VERSION);
+        assert!(!settings.close_to_tray);
+        assert_eq!(settings.zoom, 1.25);
+    }
+
+    #[test]
+    fn parse_reads_known_fields_of_newer_file() {
+        let raw = r#"{ "version": 99, "always_on_top": true, "added_later": { "a": 1 } }"#;
+        let settings = parse(raw).unwrap();
+        assert_eq!(settings.version, CURRENT_VERSION);
+        assert!(settings.always_on_top);
+    }
+
+    #[test]
+    fn parse_drops_only_bad_fields() {
+        let raw = r#"{ "zoom": "big", "close_to_tray": false, "log_level": "debug" }"#;
+        let settings = parse(raw).unwrap();
+        assert_eq!(settings.zoom, Settings::default().zoom);
+        assert!(!settings.close_to_tray);
+        assert_eq!(settings.log_level, "debug");
+    }
+
+    #[test]
+    fn parse_rejects_non_object() {
+        assert!(parse("not json").is_none());
+        assert!(parse("[1, 2]").is_none());
+    }
+}
@@ -0,0 +1,114 @@
+// User-customizable menu accelerators. Bindings are stored in settings as
+// overrides on top of `DEFAULTS`, keyed by the menu item id.
+
+use std::collections::HashMap;
+
+use tauri::menu::MenuItem;
+use tauri::{AppHandle, Manager, Runtime, Wry};
+
+use crate::settings::SettingsStore;
+
+pub const DEFAULTS: &[(&str, &str)] = &[
+    ("open_settings", "CmdOrCtrl+,"),
+    ("quit_app", "CmdOrCtrl+Q"),
+    ("reload", "CmdOrCtrl+R"),
+    ("hard_reload", "CmdOrCtrl+Shift+R"),
+    ("toggle_devtools", "CmdOrCtrl+Shift+I"),
+    ("zoom_in", "CmdOrCtrl+="),
+    ("zoom_out", "CmdOrCtrl+-"),
+    ("zoom_reset", "CmdOrCtrl+0"),
+    ("toggle_fullscreen", "F11"),
+];
+
+/// Menu items whose accelerators can be rebound, keyed by action id.
+pub struct ShortcutMenuItems(pub HashMap<String, MenuItem<Wry>>);
+
+fn default_for(action: &str) -> Option<&'static str> {
+    DEFAULTS.iter().find(|(a, _)| *a == action).map(|(_, acc)| *acc)
+}
+
+/// The effective accelerator for an action: the user's binding, else the default.
+pub fn accelerator<R: Runtime, M: Manager<R>>(manager: &M, action: &str) -> String {
+    manager
+        .state::<SettingsStore>()
+        .get()
+        .shortcuts
+        .get(action)
+        .cloned()
+        .or_else(|| default_for(action).map(String::from))
+        .unwrap_or_default()
+}
+
+/// Canonical form for comparing accelerators: lowercase, modifier aliases
+/// folded, modifiers sorted, key last. "Shift+Ctrl+R" == "ctrl+shift+r".
+fn normalize(accelerator: &str) -> String {
+    let mut parts: Vec<String> = accelerator
+        .split('+')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.as_str() {
+            "commandorcontrol" | "cmdorcontrol" | "commandorctrl" => "cmdorctrl".into(),
+            "control" => "ctrl".into(),
+            "command" | "super" | "meta" => "cmd".into(),
+            "option" => "alt".into(),
+            _ => p,
+        })
+        .collect();
+    let key = parts.pop().unwrap_or_default();
+    parts.sort();
+    parts.push(key);
+    parts.join("+")
+}
+
+fn apply_all(app: &AppHandle) {
+    let Some(items) = app.try_state::<ShortcutMenuItems>() else {
+        return;
+    };
+    for (action, item) in &items.0 {
+        let acc = accelerator(app, action);
+        if let Err(e) = item.set_accelerator(Some(acc.as_str())) {
+            log::warn!("invalid accelerator {acc} for {action}: {e}");
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_shortcuts(app: AppHandle) -> HashMap<String, String> {
+    DEFAULTS
+        .iter()
+        .map(|(action, _)| (action.to_string(), accelerator(&app, action)))
+        .collect()
+}
+
+/// Rebind `action`. Fails if the action is unknown, the accelerator doesn't
+/// parse, or it's already bound to a different action.
+#[tauri::command]
+pub fn set_shortcut(app: AppHandle, action: String, accelerator: String) -> Result<(), String> {
+    if default_for(&action).is_none() {
+        return Err(format!("unknown action \"{action}\""));
+    }
+    let wanted = normalize(&accelerator);
+    for (other, _) in DEFAULTS {
+        if *other != action && normalize(&self::accelerator(&app, other)) == wanted {
+            return Err(format!("\"{accelerator}\" is already bound to {other}"));
+        }
+    }
+
+    // Applying it to the menu item is what validates the accelerator
+    let items = app.state::<ShortcutMenuItems>();
+    let item = items.0.get(&action).ok_or("menu item not found")?;
+    item.set_accelerator(Some(accelerator.as_str()))
+        .map_err(|e| format!("invalid shortcut \"{accelerator}\": {e}"))?;
+
+    app.state::<SettingsStore>().update(&app, |s| {
+        s.shortcuts.insert(action, accelerator);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reset_shortcuts(app: AppHandle) {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.shortcuts.clear());
+    apply_all(&app);
+}
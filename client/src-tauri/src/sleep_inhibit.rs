@@ -0,0 +1,232 @@
+// Keep the machine and display awake during calls.
+//
+// - macOS: an IOKit "PreventUserIdleDisplaySleep" power assertion.
+// - Windows: SetThreadExecutionState, which is per thread, so a dedicated
+//   thread holds it for as long as the lock is wanted.
+// - Linux: the freedesktop Inhibit portal (suspend + idle). The inhibition
+//   lives as long as the D-Bus connection, which the lock keeps open.
+//
+// Requests are counted so a screenshare ending inside a call doesn't release
+// the lock early. Everything is released on exit; the OS would also drop it
+// with the process, but not every session manager notices promptly.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+
+    type CFStringRef = *const c_void;
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: *const c_void,
+            s: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    fn cf_string(s: &str) -> Result<CFStringRef, String> {
+        let s = CString::new(s).map_err(|e| e.to_string())?;
+        let cf = unsafe {
+            CFStringCreateWithCString(std::ptr::null(), s.as_ptr(), K_CF_STRING_ENCODING_UTF8)
+        };
+        if cf.is_null() {
+            Err("failed to create CFString".into())
+        } else {
+            Ok(cf)
+        }
+    }
+
+    pub struct Inhibitor(u32);
+
+    impl Inhibitor {
+        pub fn acquire(reason: &str) -> Result<Self, String> {
+            let kind = cf_string("PreventUserIdleDisplaySleep")?;
+            let name = cf_string(reason)?;
+            let mut id = 0;
+            let status = unsafe {
+                let status =
+                    IOPMAssertionCreateWithName(kind, K_IOPM_ASSERTION_LEVEL_ON, name, &mut id);
+                CFRelease(kind);
+                CFRelease(name);
+                status
+            };
+            if status == 0 {
+                Ok(Self(id))
+            } else {
+                Err(format!("IOPMAssertionCreateWithName failed ({status})"))
+            }
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            unsafe { IOPMAssertionRelease(self.0) };
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::sync::mpsc;
+    use std::thread::JoinHandle;
+
+    use windows::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+    };
+
+    pub struct Inhibitor {
+        release: Option<mpsc::Sender<()>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Inhibitor {
+        pub fn acquire(_reason: &str) -> Result<Self, String> {
+            let (release, released) = mpsc::channel::<()>();
+            let thread = std::thread::Builder::new()
+                .name("sleep-inhibit".into())
+                .spawn(move || {
+                    unsafe {
+                        SetThreadExecutionState(
+                            ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+                        )
+                    };
+                    // Blocks until released (or the sender is dropped)
+                    let _ = released.recv();
+                    unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+                })
+                .map_err(|e| e.to_string())?;
+            Ok(Self {
+                release: Some(release),
+                thread: Some(thread),
+            })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            drop(self.release.take());
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::collections::HashMap;
+
+    use zbus::blocking::Connection;
+    use zbus::zvariant::{OwnedObjectPath, Value};
+
+    const PORTAL: &str = "org.freedesktop.portal.Desktop";
+    const INHIBIT_SUSPEND: u32 = 4;
+    const INHIBIT_IDLE: u32 = 8;
+
+    pub struct Inhibitor {
+        connection: Connection,
+        request: OwnedObjectPath,
+    }
+
+    impl Inhibitor {
+        pub fn acquire(reason: &str) -> Result<Self, String> {
+            let connection = Connection::session().map_err(|e| e.to_string())?;
+            let options = HashMap::from([("reason", Value::from(reason))]);
+            let reply = connection
+                .call_method(
+                    Some(PORTAL),
+                    "/org/freedesktop/portal/desktop",
+                    Some("org.freedesktop.portal.Inhibit"),
+                    "Inhibit",
+                    &("", INHIBIT_SUSPEND | INHIBIT_IDLE, options),
+                )
+                .map_err(|e| format!("inhibit portal unavailable: {e}"))?;
+            let request = reply.body().deserialize().map_err(|e| e.to_string())?;
+            Ok(Self {
+                connection,
+                request,
+            })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            let _ = self.connection.call_method(
+                Some(PORTAL),
+                self.request.as_str(),
+                Some("org.freedesktop.portal.Request"),
+                "Close",
+                &(),
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    count: u32,
+    inhibitor: Option<platform::Inhibitor>,
+}
+
+#[derive(Default)]
+pub struct SleepInhibitState(Mutex<Inner>);
+
+/// Release the lock regardless of outstanding requests. Called on exit.
+pub fn release_all<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<SleepInhibitState>() {
+        let mut inner = state.0.lock().unwrap();
+        inner.count = 0;
+        inner.inhibitor = None;
+    }
+}
+
+/// Keep the system and display awake until a matching `uninhibit_sleep`.
+/// Calls nest.
+#[tauri::command]
+pub async fn inhibit_sleep(app: AppHandle, reason: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<SleepInhibitState>();
+        let mut inner = state.0.lock().unwrap();
+        if inner.inhibitor.is_none() {
+            inner.inhibitor = Some(platform::Inhibitor::acquire(&reason)?);
+        }
+        inner.count += 1;
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Drop one `inhibit_sleep` request; the lock goes when none are left.
+#[tauri::command]
+pub async fn uninhibit_sleep(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<SleepInhibitState>();
+        let mut inner = state.0.lock().unwrap();
+        inner.count = inner.count.saturating_sub(1);
+        if inner.count == 0 {
+            inner.inhibitor = None;
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,215 @@
+// Spell-check dictionaries for the message input.
+//
+// Only WebKit2GTK (Linux) lets us pick the dictionaries. WKWebView on macOS
+// uses the system spell checker, which detects the language as you type, and
+// WebView2 on Windows follows the OS language settings — on those platforms
+// the chosen languages are persisted but otherwise a no-op.
+//
+// Corrections for the context menu come from the same spell checkers where
+// there's an API for them: NSSpellChecker on macOS and the Windows spell
+// checking API (for the OS language). WebKitGTK doesn't expose its checker,
+// so on Linux they come from `hunspell -a` with the chosen dictionaries.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[cfg(target_os = "linux")]
+const DICTIONARY_DIRS: &[&str] = &["/usr/share/hunspell", "/usr/share/myspell", "/usr/share/myspell/dicts"];
+
+fn apply<R: Runtime>(window: &WebviewWindow<R>, languages: Vec<String>) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        window
+            .with_webview(move |webview| {
+                use webkit2gtk::{WebContextExt, WebViewExt};
+                if let Some(context) = webview.inner().context() {
+                    let langs: Vec<&str> = languages.iter().map(String::as_str).collect();
+                    context.set_spell_checking_enabled(!langs.is_empty());
+                    context.set_spell_checking_languages(&langs);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (window, languages);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lookup(word: &str, languages: &[String]) -> Vec<String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = Command::new("hunspell");
+    command.arg("-a");
+    if !languages.is_empty() {
+        command.args(["-d", &languages.join(",")]);
+    }
+    let Ok(mut child) = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return Vec::new();
+    };
+    // `^` has the rest of the line checked, even if the word starts with
+    // one of the pipe protocol's command characters
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "^{word}");
+    }
+    let Ok(output) = child.wait_with_output() else {
+        return Vec::new();
+    };
+    // A misspelling with suggestions is `& <word> <count> <offset>: a, b`
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("& ")?.split_once(": "))
+        .map(|(_, list)| list.split(", ").map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn lookup(word: &str, _languages: &[String]) -> Vec<String> {
+    use objc2::msg_send;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::{NSRange, NSString};
+
+    let Some(class) = AnyClass::get(c"NSSpellChecker") else {
+        return Vec::new();
+    };
+    let text = NSString::from_str(word);
+    let range = NSRange::new(0, text.length());
+    // SAFETY: a query on the shared spell checker. A nil language means
+    // automatic detection, as in the webview's own checking
+    unsafe {
+        let checker: *mut AnyObject = msg_send![class, sharedSpellChecker];
+        let Some(checker) = checker.as_ref() else {
+            return Vec::new();
+        };
+        let guesses: *mut AnyObject = msg_send![
+            checker,
+            guessesForWordRange: range,
+            inString: &*text,
+            language: std::ptr::null::<NSString>(),
+            inSpellDocumentWithTag: 0isize
+        ];
+        let Some(guesses) = guesses.as_ref() else {
+            return Vec::new();
+        };
+        let count: usize = msg_send![guesses, count];
+        (0..count)
+            .filter_map(|i| {
+                let guess: *mut NSString = msg_send![guesses, objectAtIndex: i];
+                guess.as_ref().map(NSString::to_string)
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn lookup(word: &str, _languages: &[String]) -> Vec<String> {
+    use std::ffi::c_void;
+
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::S_OK;
+    use windows::Win32::Globalization::{ISpellCheckerFactory, SpellCheckerFactory};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+
+    // WebView2 checks in the OS language, so the corrections do too
+    let Some(language) = crate::locale::current() else {
+        return Vec::new();
+    };
+    let suggest = || -> windows::core::Result<Vec<String>> {
+        unsafe {
+            // Harmless if this thread already joined an apartment
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let factory: ISpellCheckerFactory =
+                CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_INPROC_SERVER)?;
+            let checker = factory.CreateSpellChecker(&HSTRING::from(language))?;
+            let list = checker.Suggest(&HSTRING::from(word))?;
+            let mut suggestions = Vec::new();
+            loop {
+                let mut item = [PWSTR::null()];
+                let mut fetched = 0;
+                if list.Next(&mut item, Some(&mut fetched)) != S_OK || fetched == 0 {
+                    break;
+                }
+                suggestions.extend(item[0].to_string().ok());
+                CoTaskMemFree(Some(item[0].0 as *const c_void));
+            }
+            Ok(suggestions)
+        }
+    };
+    suggest().unwrap_or_else(|e| {
+        log::warn!("spelling suggestions failed: {e}");
+        Vec::new()
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn lookup(_word: &str, _languages: &[String]) -> Vec<String> {
+    Vec::new()
+}
+
+/// Corrections for `word`, best first, at most `max` of them. Empty when
+/// it's spelled correctly or there's no spell checker to ask.
+pub fn suggestions<R: Runtime>(app: &AppHandle<R>, word: &str, max: usize) -> Vec<String> {
+    let languages = app.state::<SettingsStore>().get().spellcheck_languages;
+    let mut suggestions = lookup(word, &languages);
+    suggestions.truncate(max);
+    suggestions
+}
+
+/// Apply the saved languages to a newly created window.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let languages = window
+        .app_handle()
+        .state::<SettingsStore>()
+        .get()
+        .spellcheck_languages;
+    if !languages.is_empty() {
+        let _ = apply(window, languages);
+    }
+}
+
+#[tauri::command]
+pub fn set_spellcheck_languages(app: AppHandle, langs: Vec<String>) -> Result<(), String> {
+    for window in app.webview_windows().values() {
+        apply(window, langs.clone())?;
+    }
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.spellcheck_languages = langs);
+    Ok(())
+}
+
+/// Installed dictionaries as locale codes (e.g. "en_US"). Empty on
+/// platforms where the dictionaries can't be chosen.
+#[tauri::command]
+pub fn get_available_spellcheck_languages() -> Vec<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut langs: Vec<String> = DICTIONARY_DIRS
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter_map(|e| {
+                let path = e.path();
+                (path.extension()? == "dic")
+                    .then(|| path.file_stem()?.to_str().map(String::from))
+                    .flatten()
+            })
+            .collect();
+        langs.sort();
+        langs.dedup();
+        langs
+    }
+    #[cfg(not(target_os = "linux"))]
+    Vec::new()
+}
@@ -0,0 +1,62 @@
+// How the main window first appears. Login launches shouldn't pop the window
+// up, so autostart registers Nexus with `--autostart`, and the "start
+// minimized" setting decides what that means. `--hidden` and `--minimized`
+// can also be passed by hand. Either way the window is only kept out of
+// sight: the tray, a second launch or a deep link still bring it back.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::{Settings, SettingsStore};
+
+/// Argument the autostart entry launches Nexus with.
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupMode {
+    Normal,
+    Minimized,
+    /// Hidden to the tray
+    Hidden,
+}
+
+impl StartupMode {
+    pub fn from_args(args: impl IntoIterator<Item = String>, settings: &Settings) -> Self {
+        let mut autostart = false;
+        for arg in args {
+            match arg.as_str() {
+                "--hidden" => return Self::Hidden,
+                "--minimized" => return Self::Minimized,
+                AUTOSTART_ARG => autostart = true,
+                _ => {}
+            }
+        }
+        if !autostart || !settings.start_minimized {
+            Self::Normal
+        } else if settings.close_to_tray {
+            Self::Hidden
+        } else {
+            // Without close-to-tray the user doesn't expect to find Nexus in
+            // the tray, so leave it on the taskbar
+            Self::Minimized
+        }
+    }
+}
+
+/// Show the freshly built main window according to `mode`.
+pub fn show<R: Runtime>(window: &WebviewWindow<R>, mode: StartupMode) -> tauri::Result<()> {
+    match mode {
+        StartupMode::Normal => window.show(),
+        StartupMode::Minimized => {
+            window.show()?;
+            window.minimize()
+        }
+        // The window was built hidden; leave it that way
+        StartupMode::Hidden => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn set_start_minimized(app: AppHandle, enabled: bool) {
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.start_minimized = enabled);
+}
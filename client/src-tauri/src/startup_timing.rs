@@ -0,0 +1,23 @@
+// Cold start timing. With `NEXUS_PROFILE_STARTUP=1` each startup stage is
+// logged with the time since `run()` began, so changes to the startup path
+// can be measured.
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+const PROFILE_ENV: &str = "NEXUS_PROFILE_STARTUP";
+
+static START: OnceLock<Option<Instant>> = OnceLock::new();
+
+/// Start the clock. Must run at the very top of `run()`.
+pub fn begin() {
+    let enabled = std::env::var(PROFILE_ENV).is_ok_and(|v| v == "1");
+    let _ = START.set(enabled.then(Instant::now));
+}
+
+/// Log that `stage` has been reached, if profiling is on.
+pub fn mark(stage: &str) {
+    if let Some(start) = START.get().copied().flatten() {
+        log::info!("startup: {stage} at {}ms", start.elapsed().as_millis());
+    }
+}
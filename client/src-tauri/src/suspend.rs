@@ -0,0 +1,302 @@
+// System sleep and wake, so the frontend can pause the voice connection
+// before the machine suspends and reconnect afterwards: `system-suspending`
+// when the OS is about to sleep, `system-resumed` on wake.
+//
+// - Linux: logind's PrepareForSleep signal. A "delay" inhibitor lock is
+//   held while awake, so logind waits for us (up to `SUSPEND_GRACE`) before
+//   suspending.
+// - macOS: IOKit system power notifications. Sleep is acknowledged with
+//   IOAllowPowerChange after the same grace period.
+// - Windows: a suspend/resume notification callback (the PBT_* codes of
+//   WM_POWERBROADCAST, without needing a window). Windows doesn't wait for
+//   apps, so the event is best effort there.
+//
+// Everything is unregistered by `shutdown` on exit.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+#[cfg(not(target_os = "windows"))]
+use std::time::Duration;
+
+use tauri::Emitter;
+
+/// How long the frontend gets to pause before the system is let go.
+#[cfg(not(target_os = "windows"))]
+const SUSPEND_GRACE: Duration = Duration::from_secs(1);
+
+type EmitFn = Box<dyn Fn(&str) + Send + Sync>;
+
+static EMIT: OnceLock<EmitFn> = OnceLock::new();
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// Emit the matching event, once per transition (Windows reports resume
+/// twice).
+fn notify(suspending: bool) {
+    if SUSPENDED.swap(suspending, Ordering::SeqCst) == suspending {
+        return;
+    }
+    log::info!("system {}", if suspending { "suspending" } else { "resumed" });
+    if let Some(emit) = EMIT.get() {
+        emit(if suspending { "system-suspending" } else { "system-resumed" });
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::sync::Mutex;
+
+    use zbus::blocking::{Connection, MessageIterator};
+    use zbus::zvariant::OwnedFd;
+    use zbus::MatchRule;
+
+    use super::{notify, SUSPEND_GRACE};
+
+    const LOGIND: &str = "org.freedesktop.login1";
+    const LOGIND_PATH: &str = "/org/freedesktop/login1";
+    const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+    /// The delay lock; dropping it closes the fd and releases it.
+    static DELAY_LOCK: Mutex<Option<OwnedFd>> = Mutex::new(None);
+
+    fn take_delay_lock(connection: &Connection) {
+        let lock = connection
+            .call_method(
+                Some(LOGIND),
+                LOGIND_PATH,
+                Some(LOGIND_MANAGER),
+                "Inhibit",
+                &("sleep", "Nexus", "Pausing the voice connection", "delay"),
+            )
+            .and_then(|reply| reply.body().deserialize::<OwnedFd>());
+        match lock {
+            Ok(fd) => *DELAY_LOCK.lock().unwrap() = Some(fd),
+            Err(e) => log::debug!("no logind delay lock: {e}"),
+        }
+    }
+
+    fn listen() -> zbus::Result<()> {
+        let connection = Connection::system()?;
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(LOGIND)?
+            .path(LOGIND_PATH)?
+            .interface(LOGIND_MANAGER)?
+            .member("PrepareForSleep")?
+            .build();
+        let messages = MessageIterator::for_match_rule(rule, &connection, None)?;
+        take_delay_lock(&connection);
+        for message in messages {
+            let Ok(start) = message.and_then(|m| m.body().deserialize::<bool>()) else {
+                continue;
+            };
+            notify(start);
+            if start {
+                std::thread::sleep(SUSPEND_GRACE);
+                DELAY_LOCK.lock().unwrap().take();
+            } else {
+                take_delay_lock(&connection);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start() -> Result<(), String> {
+        std::thread::Builder::new()
+            .name("suspend-monitor".into())
+            .spawn(|| {
+                if let Err(e) = listen() {
+                    log::warn!("not watching for system sleep: {e}");
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn stop() {
+        DELAY_LOCK.lock().unwrap().take();
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    use super::{notify, SUSPEND_GRACE};
+
+    type CFRunLoopRef = *mut c_void;
+    type CFRunLoopSourceRef = *mut c_void;
+    type CFStringRef = *const c_void;
+    type IONotificationPortRef = *mut c_void;
+    type IOServiceInterestCallback = extern "C" fn(*mut c_void, u32, u32, *mut c_void);
+
+    const K_IO_MESSAGE_CAN_SYSTEM_SLEEP: u32 = 0xE000_0270;
+    const K_IO_MESSAGE_SYSTEM_WILL_SLEEP: u32 = 0xE000_0280;
+    const K_IO_MESSAGE_SYSTEM_HAS_POWERED_ON: u32 = 0xE000_0300;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFRunLoopDefaultMode: CFStringRef;
+        fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+        fn CFRunLoopAddSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFStringRef);
+        fn CFRunLoopRun();
+        fn CFRunLoopStop(rl: CFRunLoopRef);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IORegisterForSystemPower(
+            refcon: *mut c_void,
+            port: *mut IONotificationPortRef,
+            callback: IOServiceInterestCallback,
+            notifier: *mut u32,
+        ) -> u32;
+        fn IODeregisterForSystemPower(notifier: *mut u32) -> i32;
+        fn IOServiceClose(connect: u32) -> i32;
+        fn IONotificationPortGetRunLoopSource(port: IONotificationPortRef) -> CFRunLoopSourceRef;
+        fn IONotificationPortDestroy(port: IONotificationPortRef);
+        fn IOAllowPowerChange(kernel_port: u32, notification_id: isize) -> i32;
+    }
+
+    static ROOT_PORT: AtomicU32 = AtomicU32::new(0);
+
+    /// What `stop` needs to undo the registration, as addresses so it can
+    /// cross threads.
+    struct Registration {
+        run_loop: usize,
+        port: usize,
+        notifier: u32,
+    }
+
+    static REGISTRATION: Mutex<Option<Registration>> = Mutex::new(None);
+
+    extern "C" fn on_power(_refcon: *mut c_void, _service: u32, message: u32, argument: *mut c_void) {
+        let root_port = ROOT_PORT.load(Ordering::SeqCst);
+        match message {
+            K_IO_MESSAGE_CAN_SYSTEM_SLEEP => unsafe {
+                IOAllowPowerChange(root_port, argument as isize);
+            },
+            K_IO_MESSAGE_SYSTEM_WILL_SLEEP => {
+                notify(true);
+                std::thread::sleep(SUSPEND_GRACE);
+                unsafe { IOAllowPowerChange(root_port, argument as isize) };
+            }
+            K_IO_MESSAGE_SYSTEM_HAS_POWERED_ON => notify(false),
+            _ => {}
+        }
+    }
+
+    pub fn start() -> Result<(), String> {
+        std::thread::Builder::new()
+            .name("suspend-monitor".into())
+            .spawn(|| unsafe {
+                let mut port: IONotificationPortRef = std::ptr::null_mut();
+                let mut notifier = 0;
+                let root_port =
+                    IORegisterForSystemPower(std::ptr::null_mut(), &mut port, on_power, &mut notifier);
+                if root_port == 0 {
+                    log::warn!("not watching for system sleep: IORegisterForSystemPower failed");
+                    return;
+                }
+                ROOT_PORT.store(root_port, Ordering::SeqCst);
+                let run_loop = CFRunLoopGetCurrent();
+                CFRunLoopAddSource(
+                    run_loop,
+                    IONotificationPortGetRunLoopSource(port),
+                    kCFRunLoopDefaultMode,
+                );
+                *REGISTRATION.lock().unwrap() = Some(Registration {
+                    run_loop: run_loop as usize,
+                    port: port as usize,
+                    notifier,
+                });
+                CFRunLoopRun();
+            })
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn stop() {
+        let Some(mut registration) = REGISTRATION.lock().unwrap().take() else {
+            return;
+        };
+        unsafe {
+            IODeregisterForSystemPower(&mut registration.notifier);
+            IOServiceClose(ROOT_PORT.swap(0, Ordering::SeqCst));
+            IONotificationPortDestroy(registration.port as IONotificationPortRef);
+            CFRunLoopStop(registration.run_loop as CFRunLoopRef);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ffi::c_void;
+    use std::sync::Mutex;
+
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Power::{
+        RegisterSuspendResumeNotification, UnregisterSuspendResumeNotification,
+        DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, HPOWERNOTIFY,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_CALLBACK;
+
+    use super::notify;
+
+    const PBT_APMSUSPEND: u32 = 0x0004;
+    const PBT_APMRESUMESUSPEND: u32 = 0x0007;
+    const PBT_APMRESUMEAUTOMATIC: u32 = 0x0012;
+
+    /// The registration handle, as an address so it can live in a static.
+    static REGISTRATION: Mutex<Option<isize>> = Mutex::new(None);
+
+    unsafe extern "system" fn on_power(_context: *const c_void, kind: u32, _setting: *const c_void) -> u32 {
+        match kind {
+            PBT_APMSUSPEND => notify(true),
+            PBT_APMRESUMESUSPEND | PBT_APMRESUMEAUTOMATIC => notify(false),
+            _ => {}
+        }
+        0
+    }
+
+    pub fn start() -> Result<(), String> {
+        // Must outlive the registration, which lasts until exit
+        let params: &'static DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS =
+            Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+                Callback: Some(on_power),
+                Context: std::ptr::null_mut(),
+            }));
+        let handle = unsafe {
+            RegisterSuspendResumeNotification(
+                HANDLE(params as *const _ as *mut c_void),
+                DEVICE_NOTIFY_CALLBACK,
+            )
+        }
+        .map_err(|e| e.to_string())?;
+        *REGISTRATION.lock().unwrap() = Some(handle.0);
+        Ok(())
+    }
+
+    pub fn stop() {
+        if let Some(handle) = REGISTRATION.lock().unwrap().take() {
+            let _ = unsafe { UnregisterSuspendResumeNotification(HPOWERNOTIFY(handle)) };
+        }
+    }
+}
+
+/// Start listening for sleep and wake. Called from `setup`.
+pub fn init(app: &tauri::App) {
+    let app = app.handle().clone();
+    let _ = EMIT.set(Box::new(move |event| {
+        let _ = app.emit(event, ());
+    }));
+    if let Err(e) = platform::start() {
+        log::warn!("failed to watch for system sleep: {e}");
+    }
+}
+
+/// Unregister the listeners. Called on `RunEvent::Exit`.
+pub fn shutdown() {
+    platform::stop();
+}
@@ -0,0 +1,124 @@
+// "Test Speaker": a short 440Hz sine on the chosen output device. The
+// command resolves once playback finishes or `stop_test_tone` cancels it. A
+// device that disappears mid-play surfaces through cpal's error callback and
+// fails the command instead of panicking on the audio thread.
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use tauri::{AppHandle, Manager};
+
+use crate::audio;
+
+const FREQUENCY: f32 = 440.0;
+const AMPLITUDE: f32 = 0.2;
+/// Ramp in/out so the tone doesn't start and end with a click
+const FADE_SECS: f32 = 0.01;
+const MIN_DURATION_MS: u32 = 100;
+const MAX_DURATION_MS: u32 = 10_000;
+
+enum ToneEvent {
+    Stop,
+    Error(String),
+}
+
+/// Cancels the tone that's currently playing, if any.
+#[derive(Default)]
+pub struct TestToneState(Mutex<Option<mpsc::Sender<ToneEvent>>>);
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    total_secs: f32,
+    events: mpsc::Sender<ToneEvent>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let sample_rate = config.sample_rate.0 as f32;
+    let channels = config.channels as usize;
+    let mut frame: u64 = 0;
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            for out in data.chunks_mut(channels) {
+                let t = frame as f32 / sample_rate;
+                let fade = (t / FADE_SECS).min((total_secs - t) / FADE_SECS).clamp(0.0, 1.0);
+                let value = (t * FREQUENCY * std::f32::consts::TAU).sin() * AMPLITUDE * fade;
+                for sample in out.iter_mut() {
+                    *sample = T::from_sample(value);
+                }
+                frame += 1;
+            }
+        },
+        move |e| {
+            let _ = events.send(ToneEvent::Error(e.to_string()));
+        },
+        None,
+    )
+}
+
+fn play(
+    device_id: Option<&str>,
+    duration: Duration,
+    events: mpsc::Sender<ToneEvent>,
+    rx: mpsc::Receiver<ToneEvent>,
+) -> Result<(), String> {
+    let device = audio::find_device(device_id, false).ok_or("output device not found")?;
+    let supported = device.default_output_config().map_err(|e| e.to_string())?;
+    let config = supported.config();
+    let secs = duration.as_secs_f32();
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, secs, events),
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, secs, events),
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, secs, events),
+        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, secs, events),
+        format => return Err(format!("unsupported sample format {format:?}")),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    let result = match rx.recv_timeout(duration) {
+        Ok(ToneEvent::Error(e)) => Err(format!("playback failed: {e}")),
+        Ok(ToneEvent::Stop) | Err(_) => Ok(()),
+    };
+    drop(stream);
+    result
+}
+
+fn stop(state: &TestToneState) {
+    if let Some(tx) = state.0.lock().unwrap().take() {
+        let _ = tx.send(ToneEvent::Stop);
+    }
+}
+
+/// Play a 440Hz tone on `device_id` (the selected output, or the default,
+/// when omitted) for `duration_ms`, clamped to 0.1–10s. Resolves when
+/// playback ends.
+#[tauri::command]
+pub async fn play_test_tone(
+    app: AppHandle,
+    device_id: Option<String>,
+    duration_ms: u32,
+) -> Result<(), String> {
+    let duration =
+        Duration::from_millis(duration_ms.clamp(MIN_DURATION_MS, MAX_DURATION_MS) as u64);
+    let (tx, rx) = mpsc::channel();
+    {
+        let state = app.state::<TestToneState>();
+        stop(&state);
+        *state.0.lock().unwrap() = Some(tx.clone());
+    }
+    let device_id = device_id.or_else(|| audio::selected_output(&app));
+    tauri::async_runtime::spawn_blocking(move || play(device_id.as_deref(), duration, tx, rx))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn stop_test_tone(app: AppHandle) {
+    stop(&app.state::<TestToneState>());
+}
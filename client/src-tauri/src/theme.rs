@@ -0,0 +1,231 @@
+// OS light/dark theme detection. Theme changes arrive through the window's
+// ThemeChanged event; on Linux, where GTK doesn't reliably deliver that, an
+// init script reports `prefers-color-scheme` changes from the webview
+// instead. Either path goes through `report`, which only emits
+// `system-theme-changed` when the theme actually changes.
+//
+// The window chrome can also be forced light or dark regardless of the OS
+// (`set_window_theme`). The choice is saved and passed to the window
+// builders, so there's no flash of the OS theme on startup. While a theme is
+// forced, the windows (and the webview's `prefers-color-scheme`) report it
+// as their theme, so those signals are ignored then. The OS setting itself
+// is read directly instead, and a background thread polls it so changes
+// made meanwhile are still reported:
+//
+// - macOS: the `AppleInterfaceStyle` user default ("Dark", or unset).
+// - Windows: the Personalize "AppsUseLightTheme" registry value.
+// - Linux: GNOME's `color-scheme` gsettings key.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Theme, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowTheme {
+    Light,
+    Dark,
+}
+
+impl From<WindowTheme> for Theme {
+    fn from(theme: WindowTheme) -> Self {
+        match theme {
+            WindowTheme::Light => Theme::Light,
+            WindowTheme::Dark => Theme::Dark,
+        }
+    }
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The last OS theme seen, never the forced one.
+#[derive(Default)]
+pub struct ThemeState(Mutex<Option<&'static str>>);
+
+/// Reports the webview's `prefers-color-scheme` now and whenever it changes.
+#[cfg(target_os = "linux")]
+pub const INIT_SCRIPT: &str = r#"
+(function () {
+  if (!window.matchMedia || !window.__TAURI_INTERNALS__) return;
+  var query = window.matchMedia('(prefers-color-scheme: dark)');
+  var report = function () {
+    window.__TAURI_INTERNALS__.invoke('report_system_theme', { theme: query.matches ? 'dark' : 'light' });
+  };
+  query.addEventListener('change', report);
+  report();
+})();
+"#;
+
+fn theme_name(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+fn report<R: Runtime>(app: &AppHandle<R>, theme: &'static str) {
+    let state = app.state::<ThemeState>();
+    let mut last = state.0.lock().unwrap();
+    if *last == Some(theme) {
+        return;
+    }
+    // The first report just records the starting theme
+    let changed = last.is_some();
+    *last = Some(theme);
+    if changed {
+        let _ = app.emit("system-theme-changed", theme);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn os_theme() -> Option<&'static str> {
+    use objc2::msg_send;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::NSString;
+
+    let class = AnyClass::get(c"NSUserDefaults")?;
+    let key = NSString::from_str("AppleInterfaceStyle");
+    // SAFETY: a plain lookup on the shared NSUserDefaults; the key is only
+    // set (to "Dark") while the dark appearance is on
+    unsafe {
+        let defaults: *mut AnyObject = msg_send![class, standardUserDefaults];
+        let defaults = defaults.as_ref()?;
+        let style: *mut AnyObject = msg_send![defaults, stringForKey: &*key];
+        Some(if style.is_null() { "light" } else { "dark" })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn os_theme() -> Option<&'static str> {
+    use std::ffi::c_void;
+
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let mut light: u32 = 1;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    // SAFETY: reads a DWORD into a local of the size passed
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize"),
+            w!("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut light as *mut u32 as *mut c_void),
+            Some(&mut size),
+        )
+    };
+    result
+        .is_ok()
+        .then_some(if light == 0 { "dark" } else { "light" })
+}
+
+#[cfg(target_os = "linux")]
+fn os_theme() -> Option<&'static str> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "'prefer-dark'" => Some("dark"),
+        "'default'" | "'prefer-light'" => Some("light"),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn os_theme() -> Option<&'static str> {
+    None
+}
+
+/// A theme reported by a window or its webview, which is the forced one
+/// while a theme is forced.
+fn report_from_window<R: Runtime>(app: &AppHandle<R>, theme: &'static str) {
+    if forced(app).is_none() {
+        report(app, theme);
+    }
+}
+
+fn poll_loop<R: Runtime>(app: AppHandle<R>) {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if let Some(theme) = os_theme() {
+            report(&app, theme);
+        }
+    }
+}
+
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle();
+    if app.manage(ThemeState::default()) {
+        match os_theme() {
+            Some(theme) => report(app, theme),
+            None => {
+                if let Ok(theme) = window.theme() {
+                    report_from_window(app, theme_name(theme));
+                }
+            }
+        }
+        let handle = app.clone();
+        if let Err(e) = std::thread::Builder::new()
+            .name("theme-monitor".into())
+            .spawn(move || poll_loop(handle))
+        {
+            log::warn!("failed to start theme monitor: {e}");
+        }
+    }
+
+    let app = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::ThemeChanged(theme) = event {
+            report_from_window(&app, theme_name(*theme));
+        }
+    });
+}
+
+/// The saved window theme for the window builders; `None` follows the OS.
+pub fn forced<R: Runtime, M: Manager<R>>(manager: &M) -> Option<Theme> {
+    manager
+        .state::<SettingsStore>()
+        .get()
+        .window_theme
+        .map(Into::into)
+}
+
+/// Force every window's theme, or with `None` follow the OS again.
+#[tauri::command]
+pub fn set_window_theme(app: AppHandle, theme: Option<WindowTheme>) -> Result<(), String> {
+    for window in app.webview_windows().values() {
+        window
+            .set_theme(theme.map(Into::into))
+            .map_err(|e| e.to_string())?;
+    }
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.window_theme = theme);
+    Ok(())
+}
+
+/// The forced window theme, or `None` when following the OS.
+#[tauri::command]
+pub fn get_window_theme(app: AppHandle) -> Option<WindowTheme> {
+    app.state::<SettingsStore>().get().window_theme
+}
+
+/// The OS theme, whatever theme the windows are forced to.
+#[tauri::command]
+pub fn get_system_theme(app: AppHandle) -> &'static str {
+    let last = *app.state::<ThemeState>().0.lock().unwrap();
+    last.or_else(os_theme).unwrap_or("light")
+}
+
+/// Called from `INIT_SCRIPT` on Linux.
+#[tauri::command]
+pub fn report_system_theme(app: AppHandle, theme: String) {
+    let theme = if theme == "dark" { "dark" } else { "light" };
+    report_from_window(&app, theme);
+}
@@ -0,0 +1,52 @@
+// Window titles with an optional unread count, e.g. "(3) Nexus — #general".
+// An unread indicator that works even where there's no taskbar badge. The
+// base title and count are kept per window label and the title is always
+// rebuilt from them, so prefixes never stack.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{Manager, WebviewWindow};
+
+const DEFAULT_TITLE: &str = "Nexus";
+
+#[derive(Default)]
+struct WindowTitle {
+    base: Option<String>,
+    unread: u32,
+}
+
+impl WindowTitle {
+    fn render(&self) -> String {
+        let base = self.base.as_deref().unwrap_or(DEFAULT_TITLE);
+        if self.unread > 0 {
+            format!("({}) {base}", self.unread)
+        } else {
+            base.to_string()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TitleState(Mutex<HashMap<String, WindowTitle>>);
+
+fn update(window: &WebviewWindow, f: impl FnOnce(&mut WindowTitle)) -> Result<(), String> {
+    let state = window.app_handle().state::<TitleState>();
+    let mut titles = state.0.lock().unwrap();
+    let title = titles.entry(window.label().to_string()).or_default();
+    f(title);
+    window.set_title(&title.render()).map_err(|e| e.to_string())
+}
+
+/// Set the calling window's base title, keeping any unread prefix.
+#[tauri::command]
+pub fn set_window_title(window: WebviewWindow, title: String) -> Result<(), String> {
+    update(&window, |t| t.base = Some(title))
+}
+
+/// Prefix the calling window's title with `(count)`; 0 restores the plain
+/// title.
+#[tauri::command]
+pub fn set_unread_in_title(window: WebviewWindow, count: u32) -> Result<(), String> {
+    update(&window, |t| t.unread = count)
+}
@@ -0,0 +1,339 @@
+// System tray icon and its quick-action menu.
+//
+// The tooltip summarises connection, presence and unread count, e.g.
+// "Nexus — Online — 3 unread". It follows the network monitor, the tray's
+// status menu, `set_tray_presence` and `set_badge_count`; `set_tray_tooltip`
+// replaces it with custom text. macOS shows the unread count as the menu bar
+// title next to the icon too, since the tooltip only appears on hover.
+//
+// What a left click does is configurable: open the window, show the menu,
+// or toggle the window. With "menu", a double click opens the window
+// (Windows only reports double clicks). Linux trays don't report clicks at
+// all, so there it's always the menu.
+//
+// The frontend can add its own items to the menu (`set_tray_menu`); clicks
+// on them emit `tray-menu-clicked { id }`. They go between "Open Nexus" and
+// the built-in actions, and can't remove "Open Nexus" or "Quit", so the
+// window can always be brought back and the app exited.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::image::Image;
+use tauri::menu::{
+    CheckMenuItem, CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, MenuItemKind,
+    SubmenuBuilder,
+};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Wry};
+
+use crate::settings::SettingsStore;
+
+pub const TRAY_ID: &str = "main";
+
+/// Menu ids of frontend items get this prefix, so they can't collide with
+/// the built-in ones.
+const CUSTOM_ID_PREFIX: &str = "tray_custom:";
+
+const TRAY_ICON: &[u8] = include_bytes!("../icons/tray-icon@2x.png");
+#[cfg(not(target_os = "macos"))]
+const TRAY_ICON_UNREAD: &[u8] = include_bytes!("../icons/tray-icon-unread@2x.png");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayClickBehavior {
+    /// Show and focus the window
+    Open,
+    /// Show the tray menu
+    Menu,
+    /// Show the window, or hide it if it's focused
+    Toggle,
+}
+
+impl Default for TrayClickBehavior {
+    /// Windows users expect a click to open the menu and a double click to
+    /// open the app; on macOS a click opens it.
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::Open
+        } else {
+            Self::Menu
+        }
+    }
+}
+
+/// A frontend-defined tray menu entry. With `submenu` set it opens a submenu
+/// and isn't clickable itself.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrayMenuItem {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub submenu: Option<Vec<TrayMenuItem>>,
+}
+
+#[derive(Clone, Serialize)]
+struct TrayMenuClicked {
+    id: String,
+}
+
+/// The current menu's "Mute Microphone" item, replaced with the menu.
+pub struct TrayMuteItem(Mutex<CheckMenuItem<Wry>>);
+
+#[derive(Default)]
+struct TrayStatusInner {
+    online: Option<bool>,
+    presence: Option<String>,
+    unread: u32,
+    custom: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TrayStatus(Mutex<TrayStatusInner>);
+
+fn presence_label(status: &str) -> &str {
+    match status {
+        "online" => "Online",
+        "away" => "Away",
+        "dnd" => "Do Not Disturb",
+        "invisible" => "Invisible",
+        other => other,
+    }
+}
+
+fn tooltip(status: &TrayStatusInner) -> String {
+    if let Some(custom) = &status.custom {
+        return custom.clone();
+    }
+    let mut parts = vec!["Nexus"];
+    match (status.online, &status.presence) {
+        (Some(false), _) => parts.push("Offline"),
+        (_, Some(presence)) => parts.push(presence_label(presence)),
+        _ => {}
+    }
+    let unread = format!("{} unread", status.unread);
+    if status.unread > 0 {
+        parts.push(&unread);
+    }
+    parts.join(" — ")
+}
+
+fn update_status<R: Runtime>(app: &AppHandle<R>, f: impl FnOnce(&mut TrayStatusInner)) {
+    let Some(state) = app.try_state::<TrayStatus>() else {
+        return;
+    };
+    let mut status = state.0.lock().unwrap();
+    f(&mut status);
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let _ = tray.set_tooltip(Some(tooltip(&status)));
+    #[cfg(target_os = "macos")]
+    let _ = tray.set_title((status.unread > 0).then(|| status.unread.to_string()));
+}
+
+/// Network monitor hook.
+pub fn set_connection_status<R: Runtime>(app: &AppHandle<R>, online: bool) {
+    update_status(app, |s| s.online = Some(online));
+}
+
+/// `set_badge_count` hook.
+pub fn set_unread_count<R: Runtime>(app: &AppHandle<R>, count: u32) {
+    update_status(app, |s| s.unread = count);
+}
+
+fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = crate::accounts::last_focused(app) else {
+        return;
+    };
+    let shown = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if shown && window.is_focused().unwrap_or(false) {
+        crate::close_to_tray::hide_to_tray(&window);
+    } else {
+        crate::show_main_window(app);
+    }
+}
+
+fn click_behavior<R: Runtime>(app: &AppHandle<R>) -> TrayClickBehavior {
+    app.state::<SettingsStore>().get().tray_click
+}
+
+fn custom_item<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    item: &TrayMenuItem,
+) -> tauri::Result<MenuItemKind<R>> {
+    let Some(children) = &item.submenu else {
+        let id = format!("{CUSTOM_ID_PREFIX}{}", item.id);
+        return Ok(MenuItemKind::MenuItem(
+            MenuItemBuilder::new(&item.label).id(id).build(manager)?,
+        ));
+    };
+    let mut submenu = SubmenuBuilder::new(manager, &item.label);
+    for child in children {
+        submenu = submenu.item(&custom_item(manager, child)?);
+    }
+    Ok(MenuItemKind::Submenu(submenu.build()?))
+}
+
+/// The tray menu with `custom` items, and its mute item.
+fn build_menu<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    muted: bool,
+    custom: &[TrayMenuItem],
+) -> tauri::Result<(Menu<R>, CheckMenuItem<R>)> {
+    let show_item = MenuItemBuilder::new("Open Nexus")
+        .id("tray_show")
+        .build(manager)?;
+    let mute_item = CheckMenuItemBuilder::new("Mute Microphone")
+        .id("tray_mute")
+        .checked(muted)
+        .build(manager)?;
+    let status_menu = SubmenuBuilder::new(manager, "Set Status")
+        .item(&MenuItemBuilder::new("Online").id("tray_status:online").build(manager)?)
+        .item(&MenuItemBuilder::new("Away").id("tray_status:away").build(manager)?)
+        .item(&MenuItemBuilder::new("Do Not Disturb").id("tray_status:dnd").build(manager)?)
+        .build()?;
+    let quit_item = MenuItemBuilder::new("Quit")
+        .id("tray_quit")
+        .build(manager)?;
+    let mut menu = MenuBuilder::new(manager).item(&show_item).separator();
+    for item in custom {
+        menu = menu.item(&custom_item(manager, item)?);
+    }
+    if !custom.is_empty() {
+        menu = menu.separator();
+    }
+    let menu = menu
+        .item(&mute_item)
+        .item(&status_menu)
+        .separator()
+        .item(&quit_item)
+        .build()?;
+    Ok((menu, mute_item))
+}
+
+pub fn init(app: &tauri::App) -> tauri::Result<()> {
+    app.manage(TrayStatus::default());
+    let (tray_menu, mute_item) = build_menu(app, false, &[])?;
+    app.manage(TrayMuteItem(Mutex::new(mute_item)));
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(Image::from_bytes(TRAY_ICON)?)
+        .icon_as_template(true)
+        .tooltip("Nexus")
+        .menu(&tray_menu)
+        .show_menu_on_left_click(click_behavior(app.handle()) == TrayClickBehavior::Menu)
+        .on_menu_event(|app, event| {
+            let id = event.id().as_ref();
+            match id {
+                "tray_show" => crate::show_main_window(app),
+                "tray_mute" => {
+                    let muted = app
+                        .state::<TrayMuteItem>()
+                        .0
+                        .lock()
+                        .unwrap()
+                        .is_checked()
+                        .unwrap_or(false);
+                    let _ = app.emit("tray-mute-toggle", muted);
+                }
+                "tray_quit" => {
+                    app.exit(0);
+                }
+                _ => {
+                    if let Some(status) = id.strip_prefix("tray_status:") {
+                        update_status(app, |s| s.presence = Some(status.to_string()));
+                        let _ = app.emit("tray-status-change", status);
+                    } else if let Some(id) = id.strip_prefix(CUSTOM_ID_PREFIX) {
+                        let _ = app.emit("tray-menu-clicked", TrayMenuClicked { id: id.into() });
+                    }
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            let app = tray.app_handle();
+            match (event, click_behavior(app)) {
+                (
+                    TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    },
+                    behavior,
+                ) => match behavior {
+                    TrayClickBehavior::Open => crate::show_main_window(app),
+                    TrayClickBehavior::Toggle => toggle_main_window(app),
+                    // The OS shows the menu
+                    TrayClickBehavior::Menu => {}
+                },
+                (
+                    TrayIconEvent::DoubleClick {
+                        button: MouseButton::Left,
+                        ..
+                    },
+                    TrayClickBehavior::Menu,
+                ) => crate::show_main_window(app),
+                _ => {}
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Swap the tray icon to indicate unread messages. macOS keeps the
+/// monochrome template icon, which can't carry a colored dot.
+#[tauri::command]
+pub fn set_tray_unread(app: AppHandle, unread: bool) -> Result<(), String> {
+    #[cfg(not(target_os = "macos"))]
+    {
+        let tray = app.tray_by_id(TRAY_ID).ok_or("tray icon not available")?;
+        let bytes = if unread { TRAY_ICON_UNREAD } else { TRAY_ICON };
+        let icon = Image::from_bytes(bytes).map_err(|e| e.to_string())?;
+        tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "macos")]
+    let _ = (app, unread);
+    Ok(())
+}
+
+/// Show custom text as the tray tooltip. An empty string goes back to the
+/// automatic status summary.
+#[tauri::command]
+pub fn set_tray_tooltip(app: AppHandle, text: String) {
+    update_status(&app, |s| s.custom = (!text.is_empty()).then_some(text));
+}
+
+/// The user's presence (`online`, `away`, `dnd`, ...) for the tooltip.
+#[tauri::command]
+pub fn set_tray_presence(app: AppHandle, status: String) {
+    update_status(&app, |s| s.presence = Some(status));
+}
+
+/// What a left click on the tray icon does: `open`, `menu` or `toggle`.
+#[tauri::command]
+pub fn set_tray_click_behavior(
+    app: AppHandle,
+    behavior: TrayClickBehavior,
+) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("tray icon not available")?;
+    tray.set_show_menu_on_left_click(behavior == TrayClickBehavior::Menu)
+        .map_err(|e| e.to_string())?;
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.tray_click = behavior);
+    Ok(())
+}
+
+/// Replace the frontend's tray menu items. An empty list leaves just the
+/// built-in menu.
+#[tauri::command]
+pub fn set_tray_menu(app: AppHandle, items: Vec<TrayMenuItem>) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("tray icon not available")?;
+    let state = app.state::<TrayMuteItem>();
+    let mut mute_item = state.0.lock().unwrap();
+    let muted = mute_item.is_checked().unwrap_or(false);
+    let (menu, new_mute_item) = build_menu(&app, muted, &items).map_err(|e| e.to_string())?;
+    tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+    *mute_item = new_mute_item;
+    Ok(())
+}
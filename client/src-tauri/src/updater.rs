@@ -0,0 +1,383 @@
+// Updates: release channel selection, a silent check shortly after launch,
+// and background download with a deferred install.
+//
+// The endpoint in tauri.conf.json is the stable channel; beta builds are
+// published next to it, as a rolling `beta` release. Every update check
+// (the frontend's included) goes through `build`, which points the updater
+// at the selected channel's manifest.
+//
+// A fetched manifest is reused for `MANIFEST_TTL`: the background check
+// shortly after launch warms the cache, so the Help menu check and
+// `check_for_update` answer instantly unless the result is stale, the
+// channel changed, or the caller passes `force`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::menu::MenuItem;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url, Wry};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::settings::SettingsStore;
+
+/// Delay before the startup check so it doesn't compete with first paint.
+const STARTUP_CHECK_DELAY: Duration = Duration::from_secs(5);
+
+/// How long a check's result is reused.
+const MANIFEST_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// `update-progress` goes out at most this often, and only once at least
+/// 1% more of the download has arrived (when the size is known).
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// The beta manifest, from the stable one in tauri.conf.json: the same
+/// file in the `beta` release rather than the latest one
+/// (`.../releases/latest/download/latest.json` becomes
+/// `.../releases/download/beta/latest.json`).
+fn beta_endpoint<R: Runtime>(app: &AppHandle<R>) -> Result<Url, String> {
+    let stable = app
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("endpoints"))
+        .and_then(|endpoints| endpoints.get(0))
+        .and_then(|endpoint| endpoint.as_str())
+        .ok_or("no updater endpoint in tauri.conf.json")?;
+    if !stable.contains("/releases/latest/download/") {
+        return Err(format!("can't derive the beta endpoint from {stable}"));
+    }
+    Url::parse(&stable.replace("/releases/latest/download/", "/releases/download/beta/"))
+        .map_err(|e| e.to_string())
+}
+
+/// Build an updater for the currently selected channel.
+pub fn build<R: Runtime>(app: &AppHandle<R>) -> Result<Updater, String> {
+    let settings = app.state::<SettingsStore>().get();
+    let mut builder = app.updater_builder();
+    // Stable uses the endpoint from tauri.conf.json as is
+    if settings.update_channel == UpdateChannel::Beta {
+        builder = builder
+            .endpoints(vec![beta_endpoint(app)?])
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(proxy) = settings.proxy.https_url() {
+        builder = builder.proxy(proxy);
+    }
+    if settings.allow_update_downgrade {
+        // The user confirmed leaving beta; accept the (older) stable release
+        builder = builder.version_comparator(|current, remote| remote.version != current);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// The last update found by a check, and the one downloaded and waiting to
+/// be installed (with the path of its staged package).
+#[derive(Default)]
+pub struct UpdaterState {
+    available: Mutex<Option<Update>>,
+    /// When `available` was last fetched, and for which channel
+    checked: Mutex<Option<(Instant, UpdateChannel)>>,
+    /// Held during a fetch, so concurrent checks share one request
+    fetching: tauri::async_runtime::Mutex<()>,
+    staged: Mutex<Option<(Update, PathBuf)>>,
+    /// A "Check for Updates..." check is in flight.
+    menu_check_running: AtomicBool,
+}
+
+/// The Help menu's "Check for Updates..." item, disabled during a check.
+pub struct CheckUpdatesMenuItem(pub MenuItem<Wry>);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(u: &Update) -> Self {
+        Self {
+            version: u.version.clone(),
+            notes: u.body.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UpdateCheckStatus {
+    UpToDate,
+    Available,
+    Error,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateCheckResult {
+    status: UpdateCheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+async fn check<R: Runtime>(
+    app: &AppHandle<R>,
+    force: bool,
+) -> Result<Option<UpdateInfo>, String> {
+    let state = app.state::<UpdaterState>();
+    let _fetching = state.fetching.lock().await;
+    let channel = app.state::<SettingsStore>().get().update_channel;
+    let fresh = state
+        .checked
+        .lock()
+        .unwrap()
+        .is_some_and(|(at, c)| c == channel && at.elapsed() < MANIFEST_TTL);
+    if fresh && !force {
+        return Ok(state.available.lock().unwrap().as_ref().map(UpdateInfo::from));
+    }
+
+    let update = build(app)?.check().await.map_err(|e| e.to_string())?;
+    let info = update.as_ref().map(UpdateInfo::from);
+    *state.available.lock().unwrap() = update;
+    *state.checked.lock().unwrap() = Some((Instant::now(), channel));
+    Ok(info)
+}
+
+/// Check for updates in the background a few seconds after launch and emit
+/// `update-available` if one is found. Never blocks startup, and leaves
+/// the result cached for later checks.
+pub fn spawn_startup_check<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_CHECK_DELAY).await;
+        match check(&app, false).await {
+            Ok(Some(info)) => {
+                let _ = app.emit("update-available", info);
+            }
+            Ok(None) => {}
+            Err(e) => log::info!("startup update check failed: {e}"),
+        }
+    });
+}
+
+/// Run a check for the Help menu and report it as `update-check-result`.
+/// Clicks while a check is running are ignored.
+pub fn check_from_menu(app: &AppHandle) {
+    let state = app.state::<UpdaterState>();
+    if state.menu_check_running.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let set_menu_enabled = |app: &AppHandle, enabled: bool| {
+        if let Some(item) = app.try_state::<CheckUpdatesMenuItem>() {
+            let _ = item.0.set_enabled(enabled);
+        }
+    };
+    set_menu_enabled(app, false);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match check(&app, false).await {
+            Ok(Some(info)) => UpdateCheckResult {
+                status: UpdateCheckStatus::Available,
+                version: Some(info.version),
+                error: None,
+            },
+            Ok(None) => UpdateCheckResult {
+                status: UpdateCheckStatus::UpToDate,
+                version: None,
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("update check failed: {e}");
+                UpdateCheckResult {
+                    status: UpdateCheckStatus::Error,
+                    version: None,
+                    error: Some(e),
+                }
+            }
+        };
+        let _ = app.emit("update-check-result", result);
+        set_menu_enabled(&app, true);
+        app.state::<UpdaterState>()
+            .menu_check_running
+            .store(false, Ordering::SeqCst);
+    });
+}
+
+fn staging_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = crate::paths::cache_dir(app)
+        .map_err(|e| e.to_string())?
+        .join("updates");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// The package's size from a HEAD request, where the server reports it.
+async fn download_size(app: &AppHandle, update: &Update) -> Option<u64> {
+    let response = crate::http::client(app)
+        .ok()?
+        .head(update.download_url.clone())
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.content_length().filter(|&len| len > 0)
+}
+
+/// Check the selected channel for an update. Reuses a recent result unless
+/// `force` is set.
+#[tauri::command]
+pub async fn check_for_update(
+    app: AppHandle,
+    force: Option<bool>,
+) -> Result<Option<UpdateInfo>, String> {
+    check(&app, force.unwrap_or(false)).await
+}
+
+/// Download the selected channel's update without installing it, emitting
+/// throttled `update-progress` as bytes arrive, ending with a final event
+/// at 100%. The package is written to the cache
+/// dir; a failed download leaves nothing behind. Fails with
+/// `insufficient_space` if the package won't fit.
+#[tauri::command]
+pub async fn download_and_stage_update(app: AppHandle) -> Result<UpdateInfo, String> {
+    // Refetches unless the cached result is recent and for this channel
+    check(&app, false).await?;
+    let update = app
+        .state::<UpdaterState>()
+        .available
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("no update available")?;
+
+    let dir = staging_dir(&app)?;
+    let partial = dir.join(format!("{}.part", update.version));
+    let staged = dir.join(format!("{}.pkg", update.version));
+    if let Some(size) = download_size(&app, &update).await {
+        crate::disk_space::ensure_available(&dir, size, "the update")?;
+    }
+
+    let mut downloaded: u64 = 0;
+    let mut last_emit: Option<(Instant, u64)> = None;
+    let progress_app = app.clone();
+    let bytes = update
+        .download(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let due = match last_emit {
+                    None => true,
+                    Some((at, bytes)) => {
+                        let step = total.map_or(0, |t| t / 100);
+                        at.elapsed() >= PROGRESS_INTERVAL && downloaded - bytes >= step
+                    }
+                };
+                if due {
+                    last_emit = Some((Instant::now(), downloaded));
+                    let _ = progress_app.emit("update-progress", UpdateProgress { downloaded, total });
+                }
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    let size = bytes.len() as u64;
+    // The size may not have been known up front
+    crate::disk_space::ensure_available(&dir, size, "the update")?;
+    let _ = app.emit(
+        "update-progress",
+        UpdateProgress {
+            downloaded: size,
+            total: Some(size),
+        },
+    );
+
+    let write = std::fs::write(&partial, &bytes).and_then(|_| std::fs::rename(&partial, &staged));
+    if let Err(e) = write {
+        let _ = std::fs::remove_file(&partial);
+        return Err(format!("failed to stage update: {e}"));
+    }
+
+    let info = UpdateInfo::from(&update);
+    *app.state::<UpdaterState>().staged.lock().unwrap() = Some((update, staged));
+    Ok(info)
+}
+
+/// Install the staged update and relaunch.
+#[tauri::command]
+pub async fn install_staged_update(app: AppHandle) -> Result<(), String> {
+    let (update, path) = app
+        .state::<UpdaterState>()
+        .staged
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("no staged update")?;
+    let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+    update.install(bytes).map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(&path);
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.allow_update_downgrade = false);
+    app.restart()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelChange {
+    pub channel: UpdateChannel,
+    /// Leaving beta may mean installing an older stable build. When true, the
+    /// channel was not changed; call again with `confirmed: true` to proceed.
+    pub requires_confirmation: bool,
+}
+
+#[tauri::command]
+pub fn get_update_channel(app: AppHandle) -> UpdateChannel {
+    app.state::<SettingsStore>().get().update_channel
+}
+
+#[tauri::command]
+pub fn set_update_channel(
+    app: AppHandle,
+    channel: UpdateChannel,
+    confirmed: Option<bool>,
+) -> ChannelChange {
+    let store = app.state::<SettingsStore>();
+    let current = store.get().update_channel;
+    let downgrade = current == UpdateChannel::Beta && channel == UpdateChannel::Stable;
+    if downgrade && !confirmed.unwrap_or(false) {
+        return ChannelChange {
+            channel: current,
+            requires_confirmation: true,
+        };
+    }
+    store.update(&app, |s| {
+        s.update_channel = channel;
+        s.allow_update_downgrade = downgrade;
+    });
+    // The downgrade flag changes what counts as an update
+    let state = app.state::<UpdaterState>();
+    state.checked.lock().unwrap().take();
+    state.available.lock().unwrap().take();
+    ChannelChange {
+        channel,
+        requires_confirmation: false,
+    }
+}
@@ -0,0 +1,84 @@
+// The webview's user agent: the platform default with ` Nexus/<version>`
+// appended, so the server can tell the desktop client from a browser.
+// `NEXUS_USER_AGENT` replaces the whole string, for testing.
+//
+// WebKitGTK's default is read from and written back to the webview's
+// settings after creation. WebView2 and WKWebView only take a user agent at
+// construction, before there's a default to read, so theirs is rebuilt from
+// the webview version.
+
+use std::sync::OnceLock;
+
+const ENV_OVERRIDE: &str = "NEXUS_USER_AGENT";
+const SUFFIX: &str = concat!(" Nexus/", env!("CARGO_PKG_VERSION"));
+
+/// What the webviews were given, once known.
+static USER_AGENT: OnceLock<String> = OnceLock::new();
+
+fn env_override() -> Option<String> {
+    std::env::var(ENV_OVERRIDE)
+        .ok()
+        .map(|ua| ua.trim().to_string())
+        .filter(|ua| !ua.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_default() -> String {
+    // Matches WebView2's own, which reduces the Chrome version to its major
+    let edge = tauri::webview_version().unwrap_or_else(|_| "120.0.0.0".into());
+    let major = edge.split('.').next().unwrap_or("120");
+    format!(
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+         Chrome/{major}.0.0.0 Safari/537.36 Edg/{edge}"
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn platform_default() -> String {
+    // WKWebView's default; it's frozen and has no Safari version in it
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko)"
+        .to_string()
+}
+
+/// The user agent for a new webview's builder.
+#[cfg(not(target_os = "linux"))]
+pub fn get() -> String {
+    USER_AGENT
+        .get_or_init(|| env_override().unwrap_or_else(|| format!("{}{SUFFIX}", platform_default())))
+        .clone()
+}
+
+/// Append the suffix to the webview's default user agent. Call once per
+/// window, right after it's built.
+#[cfg(target_os = "linux")]
+pub fn apply<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) {
+    let result = window.with_webview(|webview| {
+        use webkit2gtk::{SettingsExt, WebViewExt};
+
+        let Some(settings) = WebViewExt::settings(&webview.inner()) else {
+            return;
+        };
+        let ua = USER_AGENT.get_or_init(|| {
+            env_override().unwrap_or_else(|| {
+                let default = settings.user_agent().map(|ua| ua.to_string()).unwrap_or_default();
+                format!("{}{SUFFIX}", default.trim_end())
+            })
+        });
+        settings.set_user_agent(Some(ua));
+    });
+    if let Err(e) = result {
+        log::warn!("failed to set the user agent: {e}");
+    }
+}
+
+/// The user agent the webviews send.
+#[tauri::command]
+pub fn get_user_agent() -> Result<String, String> {
+    #[cfg(not(target_os = "linux"))]
+    return Ok(get());
+    #[cfg(target_os = "linux")]
+    USER_AGENT
+        .get()
+        .cloned()
+        .ok_or_else(|| "the user agent isn't set yet".to_string())
+}
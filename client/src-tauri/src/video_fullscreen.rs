@@ -0,0 +1,155 @@
+// Element fullscreen (a video's fullscreen button) becomes real window
+// fullscreen, so the video fills the screen rather than just the webview.
+// The request is picked up from WebKitGTK's enter/leave-fullscreen signals,
+// WebView2's ContainsFullScreenElementChanged, and on macOS from the page's
+// `fullscreenchange` event via `report_video_fullscreen`. Each change emits
+// `video-fullscreen-changed { fullscreen }` to the window.
+//
+// The window's prior fullscreen state is restored on exit. If the user
+// toggles fullscreen from the View menu in between, that choice wins and
+// leaving the video's fullscreen doesn't undo it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
+
+/// Windows in video fullscreen, with whether to go back to windowed on exit
+#[derive(Default)]
+pub struct VideoFullscreenState(Mutex<HashMap<String, bool>>);
+
+#[derive(Clone, Serialize)]
+struct VideoFullscreenChanged {
+    fullscreen: bool,
+}
+
+/// Page script for platforms without a native signal.
+#[cfg(target_os = "macos")]
+pub const INIT_SCRIPT: &str = r#"
+(() => {
+  const report = () =>
+    window.__TAURI_INTERNALS__?.invoke("report_video_fullscreen", {
+      active: !!(document.fullscreenElement || document.webkitFullscreenElement),
+    });
+  document.addEventListener("fullscreenchange", report);
+  document.addEventListener("webkitfullscreenchange", report);
+})();
+"#;
+
+fn enter<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(state) = window.app_handle().try_state::<VideoFullscreenState>() else {
+        return;
+    };
+    let mut windows = state.0.lock().unwrap();
+    if windows.contains_key(window.label()) {
+        return;
+    }
+    let was_fullscreen = window.is_fullscreen().unwrap_or(false);
+    windows.insert(window.label().to_string(), !was_fullscreen);
+    drop(windows);
+    if !was_fullscreen {
+        let _ = window.set_fullscreen(true);
+    }
+    let _ = window.emit_to(
+        window.label(),
+        "video-fullscreen-changed",
+        VideoFullscreenChanged { fullscreen: true },
+    );
+}
+
+fn leave<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(state) = window.app_handle().try_state::<VideoFullscreenState>() else {
+        return;
+    };
+    let restore_windowed = state.0.lock().unwrap().remove(window.label());
+    let Some(restore_windowed) = restore_windowed else {
+        return;
+    };
+    if restore_windowed {
+        let _ = window.set_fullscreen(false);
+    }
+    let _ = window.emit_to(
+        window.label(),
+        "video-fullscreen-changed",
+        VideoFullscreenChanged { fullscreen: false },
+    );
+}
+
+/// The user changed fullscreen themselves (View menu); don't restore
+/// anything when the video leaves fullscreen.
+pub fn forget<R: Runtime>(window: &WebviewWindow<R>) {
+    if let Some(state) = window.app_handle().try_state::<VideoFullscreenState>() {
+        if let Some(restore) = state.0.lock().unwrap().get_mut(window.label()) {
+            *restore = false;
+        }
+    }
+}
+
+/// Hook the webview's fullscreen requests. Call once per window; on macOS
+/// the window's builder needs `INIT_SCRIPT` instead.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) -> tauri::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        let w = window.clone();
+        window.with_webview(move |webview| {
+            use webkit2gtk::WebViewExt;
+
+            let wv = webview.inner();
+            let on_enter = w.clone();
+            // Returning true stops WebKit fullscreening the window itself, so
+            // the prior state is ours to track
+            wv.connect_enter_fullscreen(move |_| {
+                enter(&on_enter);
+                true
+            });
+            wv.connect_leave_fullscreen(move |_| {
+                leave(&w);
+                true
+            });
+        })?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let w = window.clone();
+        window.with_webview(move |webview| {
+            unsafe {
+                use webview2_com::ContainsFullScreenElementChangedEventHandler;
+
+                let core = webview.controller().CoreWebView2().unwrap();
+                let handler = ContainsFullScreenElementChangedEventHandler::create(
+                    Box::new(move |sender, _args| {
+                        if let Some(core) = sender {
+                            let mut contains = windows::core::BOOL::default();
+                            core.ContainsFullScreenElement(&mut contains)?;
+                            if contains.as_bool() {
+                                enter(&w);
+                            } else {
+                                leave(&w);
+                            }
+                        }
+                        Ok(())
+                    }),
+                );
+                let mut token = std::mem::zeroed();
+                core.add_ContainsFullScreenElementChanged(&handler, &mut token)
+                    .unwrap();
+            }
+        })?;
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = window;
+    Ok(())
+}
+
+/// Called by `INIT_SCRIPT` when the page enters or leaves fullscreen.
+#[tauri::command]
+pub fn report_video_fullscreen(window: WebviewWindow, active: bool) {
+    if active {
+        enter(&window);
+    } else {
+        leave(&window);
+    }
+}
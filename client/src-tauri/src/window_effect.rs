@@ -0,0 +1,153 @@
+// Blurred backdrop behind the main window, for a translucent sidebar:
+// Mica or Acrylic on Windows 11, NSVisualEffectView vibrancy on macOS. The
+// frontend leaves the areas it wants blurred transparent.
+//
+// The effect only shows through a transparent window, and transparency is
+// fixed when the window is created. So the window is only made transparent
+// when an effect is saved at startup, and turning one on from "none" takes
+// a restart. Effects the OS can't do (Mica before Windows 11, anything on
+// Linux) are reported as unsupported rather than failing.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    Mica,
+    Acrylic,
+    Vibrancy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectOutcome {
+    Applied,
+    /// Not available on this OS or OS version
+    Unsupported,
+    /// Saved; the window needs to be recreated transparent first
+    RestartRequired,
+}
+
+/// Whether the main window was created transparent.
+static TRANSPARENT: AtomicBool = AtomicBool::new(false);
+
+/// Whether the main window should be created transparent. Call once, for
+/// its builder.
+pub fn wants_transparency<R: Runtime, M: Manager<R>>(manager: &M) -> bool {
+    let transparent = cfg!(any(target_os = "macos", target_os = "windows"))
+        && manager.state::<SettingsStore>().get().window_effect != WindowEffect::None;
+    TRANSPARENT.store(transparent, Ordering::Relaxed);
+    transparent
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn outcome(result: Result<(), window_vibrancy::Error>) -> Result<EffectOutcome, String> {
+    use window_vibrancy::Error;
+
+    match result {
+        Ok(()) => Ok(EffectOutcome::Applied),
+        Err(Error::UnsupportedPlatform(_) | Error::UnsupportedPlatformVersion(_)) => {
+            Ok(EffectOutcome::Unsupported)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply_native<R: Runtime>(
+    window: &WebviewWindow<R>,
+    effect: WindowEffect,
+) -> Result<EffectOutcome, String> {
+    let _ = window_vibrancy::clear_mica(window);
+    let _ = window_vibrancy::clear_acrylic(window);
+    match effect {
+        WindowEffect::None => Ok(EffectOutcome::Applied),
+        // Follows the system light/dark setting
+        WindowEffect::Mica => outcome(window_vibrancy::apply_mica(window, None)),
+        WindowEffect::Acrylic => outcome(window_vibrancy::apply_acrylic(window, None)),
+        WindowEffect::Vibrancy => Ok(EffectOutcome::Unsupported),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_native<R: Runtime>(
+    window: &WebviewWindow<R>,
+    effect: WindowEffect,
+) -> Result<EffectOutcome, String> {
+    use window_vibrancy::NSVisualEffectMaterial;
+
+    let _ = window_vibrancy::clear_vibrancy(window);
+    match effect {
+        WindowEffect::None => Ok(EffectOutcome::Applied),
+        WindowEffect::Vibrancy => outcome(window_vibrancy::apply_vibrancy(
+            window,
+            NSVisualEffectMaterial::Sidebar,
+            None,
+            None,
+        )),
+        WindowEffect::Mica | WindowEffect::Acrylic => Ok(EffectOutcome::Unsupported),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply_native<R: Runtime>(
+    _window: &WebviewWindow<R>,
+    effect: WindowEffect,
+) -> Result<EffectOutcome, String> {
+    Ok(match effect {
+        WindowEffect::None => EffectOutcome::Applied,
+        _ => EffectOutcome::Unsupported,
+    })
+}
+
+/// Apply the persisted effect to the main window at startup. Main thread
+/// only.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let effect = window.app_handle().state::<SettingsStore>().get().window_effect;
+    if effect == WindowEffect::None || !TRANSPARENT.load(Ordering::Relaxed) {
+        return;
+    }
+    match apply_native(window, effect) {
+        Ok(EffectOutcome::Unsupported) => {
+            log::info!("window effect {effect:?} isn't supported here");
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("failed to apply window effect: {e}"),
+    }
+}
+
+/// Set the main window's backdrop: `mica`, `acrylic`, `vibrancy` or `none`.
+/// The choice is saved even when it can't be shown right now.
+#[tauri::command]
+pub async fn set_window_effect(
+    app: AppHandle,
+    effect: WindowEffect,
+) -> Result<EffectOutcome, String> {
+    let window = app.get_webview_window("main").ok_or("main window not found")?;
+    app.state::<SettingsStore>()
+        .update(&app, |s| s.window_effect = effect);
+    if effect != WindowEffect::None && !TRANSPARENT.load(Ordering::Relaxed) {
+        return Ok(if cfg!(any(target_os = "macos", target_os = "windows")) {
+            EffectOutcome::RestartRequired
+        } else {
+            EffectOutcome::Unsupported
+        });
+    }
+    // The native calls need the main thread
+    let (tx, rx) = std::sync::mpsc::channel();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(apply_native(&window, effect));
+    })
+    .map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || rx.recv())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+}
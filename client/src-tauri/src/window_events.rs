@@ -0,0 +1,79 @@
+// Focus and visibility changes, emitted to each window's own label so the
+// frontend can pause rendering in whichever window is in the background:
+// `window-focus-changed { focused }` and
+// `window-visibility-changed { visible }`, where visible means shown and not
+// minimized.
+//
+// There's no window event for minimize or hide, so visibility is rechecked
+// on the events that accompany them (focus changes, resizes) and by
+// `refresh` after the app hides or shows windows itself.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// Last visibility sent per window label.
+#[derive(Default)]
+pub struct WindowVisibility(Mutex<HashMap<String, bool>>);
+
+#[derive(Clone, Serialize)]
+struct FocusChanged {
+    focused: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct VisibilityChanged {
+    visible: bool,
+}
+
+fn sync_visibility<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(state) = window.app_handle().try_state::<WindowVisibility>() else {
+        return;
+    };
+    let visible =
+        window.is_visible().unwrap_or(true) && !window.is_minimized().unwrap_or(false);
+    let previous = state
+        .0
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), visible);
+    if previous != Some(visible) {
+        let _ = window.emit_to(
+            window.label(),
+            "window-visibility-changed",
+            VisibilityChanged { visible },
+        );
+    }
+}
+
+/// Recheck every window's visibility. Call after hiding or showing windows
+/// from Rust.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    for window in app.webview_windows().values() {
+        sync_visibility(window);
+    }
+}
+
+/// Forward the window's focus and visibility changes. Call once per window.
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    let w = window.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::Focused(focused) => {
+            let _ = w.emit_to(
+                w.label(),
+                "window-focus-changed",
+                FocusChanged { focused: *focused },
+            );
+            sync_visibility(&w);
+        }
+        tauri::WindowEvent::Resized(_) => sync_visibility(&w),
+        tauri::WindowEvent::Destroyed => {
+            if let Some(state) = w.app_handle().try_state::<WindowVisibility>() {
+                state.0.lock().unwrap().remove(w.label());
+            }
+        }
+        _ => {}
+    });
+}
@@ -0,0 +1,47 @@
+// Per-window icons, so each account window can show its account's avatar.
+// macOS has no per-window icons; there the calls succeed without visible
+// effect.
+
+use tauri::image::Image;
+use tauri::WebviewWindow;
+
+const DEFAULT_ICON: &[u8] = include_bytes!("../icons/icon.png");
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const MIN_SIZE: u32 = 16;
+const MAX_SIZE: u32 = 1024;
+
+/// The bundled app icon.
+pub fn default_icon() -> tauri::Result<Image<'static>> {
+    Image::from_bytes(DEFAULT_ICON)
+}
+
+fn decode(bytes: &[u8]) -> Result<Image<'_>, String> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err("window icon must be a PNG".into());
+    }
+    let image = Image::from_bytes(bytes).map_err(|e| format!("invalid PNG: {e}"))?;
+    let (width, height) = (image.width(), image.height());
+    if width != height {
+        return Err(format!("window icon must be square, got {width}x{height}"));
+    }
+    if !(MIN_SIZE..=MAX_SIZE).contains(&width) {
+        return Err(format!(
+            "window icon must be {MIN_SIZE}–{MAX_SIZE}px, got {width}x{height}"
+        ));
+    }
+    Ok(image)
+}
+
+/// Use `bytes` (a square PNG) as the calling window's icon.
+#[tauri::command]
+pub fn set_window_icon(window: WebviewWindow, bytes: Vec<u8>) -> Result<(), String> {
+    let icon = decode(&bytes)?;
+    window.set_icon(icon).map_err(|e| e.to_string())
+}
+
+/// Go back to the bundled icon.
+#[tauri::command]
+pub fn reset_window_icon(window: WebviewWindow) -> Result<(), String> {
+    let icon = default_icon().map_err(|e| e.to_string())?;
+    window.set_icon(icon).map_err(|e| e.to_string())
+}
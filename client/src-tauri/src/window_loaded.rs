@@ -0,0 +1,57 @@
+// Which windows have finished loading their page, from the webview's own
+// load-finished notification. The flag drops again when a new load starts.
+//
+// When a new load starts in the main window, deep links are buffered again
+// until the new page pulls them (see `deep_link::main_window_unloaded`).
+// Each flip to loaded is emitted as `window-loaded { label }`.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// Labels of the windows whose current page has finished loading.
+#[derive(Default)]
+pub struct LoadedWindows(Mutex<HashSet<String>>);
+
+#[derive(Clone, Serialize)]
+struct WindowLoaded {
+    label: String,
+}
+
+/// Page load hook for the window builders' `on_page_load`.
+pub fn on_page_load<R: Runtime>(window: &WebviewWindow<R>, payload: &PageLoadPayload<'_>) {
+    // Whatever replaces the main window's page has to pull links again,
+    // including the offline page, which never will
+    if window.label() == "main" && matches!(payload.event(), PageLoadEvent::Started) {
+        crate::deep_link::main_window_unloaded(window.app_handle());
+    }
+    // The offline page isn't the app, so it doesn't count as loaded
+    if crate::load_errors::is_offline_page(payload.url()) {
+        return;
+    }
+    let Some(state) = window.app_handle().try_state::<LoadedWindows>() else {
+        return;
+    };
+    let label = window.label().to_string();
+    match payload.event() {
+        PageLoadEvent::Started => {
+            state.0.lock().unwrap().remove(&label);
+        }
+        PageLoadEvent::Finished => {
+            if !state.0.lock().unwrap().insert(label.clone()) {
+                return;
+            }
+            let _ = window.app_handle().emit("window-loaded", WindowLoaded { label });
+        }
+    }
+}
+
+/// Whether the window `label` exists and its page has finished loading.
+#[tauri::command]
+pub fn is_window_loaded(app: AppHandle, label: String) -> bool {
+    app.get_webview_window(&label).is_some()
+        && app.state::<LoadedWindows>().0.lock().unwrap().contains(&label)
+}
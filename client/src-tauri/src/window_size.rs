@@ -0,0 +1,44 @@
+// Size limits for the app windows. Account windows (main included) start
+// with `DEFAULT_MIN_SIZE`, below which the layout stops working; the
+// frontend can tighten or relax the limits per window at runtime.
+
+use serde::Deserialize;
+use tauri::{LogicalSize, WebviewWindow};
+
+/// Smallest logical size the responsive layout handles.
+pub const DEFAULT_MIN_SIZE: (f64, f64) = (940.0, 560.0);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Size {
+    fn validate(self, what: &str) -> Result<LogicalSize<f64>, String> {
+        let valid = |v: f64| v.is_finite() && v > 0.0;
+        if !valid(self.width) || !valid(self.height) {
+            return Err(format!("invalid {what} size {}x{}", self.width, self.height));
+        }
+        Ok(LogicalSize::new(self.width, self.height))
+    }
+}
+
+/// Set the calling window's minimum and maximum logical size. `None`
+/// removes that limit, including the default minimum.
+#[tauri::command]
+pub fn set_window_size_constraints(
+    window: WebviewWindow,
+    min: Option<Size>,
+    max: Option<Size>,
+) -> Result<(), String> {
+    let min = min.map(|s| s.validate("minimum")).transpose()?;
+    let max = max.map(|s| s.validate("maximum")).transpose()?;
+    if let (Some(min), Some(max)) = (min, max) {
+        if min.width > max.width || min.height > max.height {
+            return Err("minimum size is larger than the maximum".into());
+        }
+    }
+    window.set_min_size(min).map_err(|e| e.to_string())?;
+    window.set_max_size(max).map_err(|e| e.to_string())
+}
@@ -0,0 +1,220 @@
+// Persists window geometry under the app config dir so windows reopen where
+// the user left them; each window label gets its own file. Writes are
+// debounced: Moved/Resized fire for every pixel while dragging, so we only
+// save once the window has settled.
+//
+// The saved bounds are always the "normal" (un-maximized, non-fullscreen)
+// ones, with maximized/fullscreen recorded as flags on top, so leaving a
+// restored maximized window returns to a sensible size.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+const STATE_FILE_STEM: &str = "window_state";
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    maximized: bool,
+    #[serde(default)]
+    fullscreen: bool,
+}
+
+fn state_path<R: Runtime>(window: &WebviewWindow<R>) -> Option<PathBuf> {
+    // The main window keeps the original file name so existing state survives
+    let file = match window.label() {
+        "main" => format!("{STATE_FILE_STEM}.json"),
+        label => format!("{STATE_FILE_STEM}.{label}.json"),
+    };
+    crate::paths::config_dir(window.app_handle())
+        .ok()
+        .map(|d| d.join(file))
+}
+
+fn load<R: Runtime>(window: &WebviewWindow<R>) -> Option<WindowGeometry> {
+    let raw = std::fs::read_to_string(state_path(window)?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Save the current state. `normal` holds the last un-maximized bounds and
+/// is what gets written while the window is maximized or fullscreen.
+fn save<R: Runtime>(window: &WebviewWindow<R>, normal: &mut Option<WindowGeometry>) {
+    // A minimized window reports a bogus off-screen position on Windows
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(pos), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    let current = WindowGeometry {
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+        fullscreen: false,
+    };
+    let bounds = if maximized || fullscreen {
+        // Never seen un-maximized: the current bounds are the best we have
+        *normal.get_or_insert(current)
+    } else {
+        *normal = Some(current);
+        current
+    };
+    let geometry = WindowGeometry {
+        maximized,
+        fullscreen,
+        ..bounds
+    };
+    let Some(path) = state_path(window) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&geometry) {
+        if let Err(e) = std::fs::write(&path, json) {
+            log::warn!("failed to save window state: {e}");
+        }
+    }
+}
+
+/// Pick the monitor the saved window overlaps, falling back to the primary
+/// monitor when the one it was on is no longer connected.
+fn monitor_for<R: Runtime>(window: &WebviewWindow<R>, g: &WindowGeometry) -> Option<Monitor> {
+    let center_x = g.x + (g.width / 2) as i32;
+    let center_y = g.y + (g.height / 2) as i32;
+    window
+        .available_monitors()
+        .ok()
+        .and_then(|monitors| {
+            monitors.into_iter().find(|m| {
+                let p = m.position();
+                let s = m.size();
+                center_x >= p.x
+                    && center_x < p.x + s.width as i32
+                    && center_y >= p.y
+                    && center_y < p.y + s.height as i32
+            })
+        })
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+fn clamp_to_monitor(g: WindowGeometry, monitor: &Monitor) -> WindowGeometry {
+    let mp = monitor.position();
+    let ms = monitor.size();
+    let width = g.width.min(ms.width);
+    let height = g.height.min(ms.height);
+    let max_x = mp.x + (ms.width - width) as i32;
+    let max_y = mp.y + (ms.height - height) as i32;
+    WindowGeometry {
+        x: g.x.clamp(mp.x, max_x),
+        y: g.y.clamp(mp.y, max_y),
+        width,
+        height,
+        ..g
+    }
+}
+
+/// Apply the saved geometry, if any, then re-maximize or re-enter
+/// fullscreen on the monitor it was positioned on. Call before the window is
+/// shown. Returns false when there was no usable saved state, leaving the
+/// builder defaults in place.
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) -> bool {
+    let Some(saved) = load(window) else {
+        return false;
+    };
+    let geometry = match monitor_for(window, &saved) {
+        Some(monitor) => clamp_to_monitor(saved, &monitor),
+        None => saved,
+    };
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.fullscreen {
+        let _ = window.set_fullscreen(true);
+    } else if geometry.maximized {
+        let _ = window.maximize();
+    }
+    true
+}
+
+/// First-launch sizing: `fraction` of the work area of the monitor under the
+/// cursor (so a 4K screen doesn't get a tiny window), no smaller than
+/// `min_logical`, centered on that monitor.
+pub fn apply_default<R: Runtime>(window: &WebviewWindow<R>, fraction: f64, min_logical: (f64, f64)) {
+    let monitor = window
+        .cursor_position()
+        .ok()
+        .and_then(|p| window.monitor_from_point(p.x, p.y).ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else {
+        return;
+    };
+    let area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let width = (area.size.width as f64 * fraction)
+        .max(min_logical.0 * scale)
+        .min(area.size.width as f64) as u32;
+    let height = (area.size.height as f64 * fraction)
+        .max(min_logical.1 * scale)
+        .min(area.size.height as f64) as u32;
+    let x = area.position.x + ((area.size.width - width) / 2) as i32;
+    let y = area.position.y + ((area.size.height - height) / 2) as i32;
+    let _ = window.set_size(PhysicalSize::new(width, height));
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+}
+
+/// Save geometry whenever the window is moved or resized, debounced.
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    // Time of the most recent Moved/Resized event, and whether a save
+    // thread is already waiting for the window to settle.
+    let pending: Arc<Mutex<(Instant, bool)>> = Arc::new(Mutex::new((Instant::now(), false)));
+    // The saved bounds are the normal ones even if the flags are set
+    let normal = load(window).map(|g| WindowGeometry {
+        maximized: false,
+        fullscreen: false,
+        ..g
+    });
+    let normal = Arc::new(Mutex::new(normal));
+    let window_for_events = window.clone();
+    window.on_window_event(move |event| {
+        if !matches!(
+            event,
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+        ) {
+            return;
+        }
+        let mut guard = pending.lock().unwrap();
+        guard.0 = Instant::now();
+        if guard.1 {
+            return;
+        }
+        guard.1 = true;
+        drop(guard);
+
+        let pending = pending.clone();
+        let normal = normal.clone();
+        let window = window_for_events.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(SAVE_DEBOUNCE);
+            let mut guard = pending.lock().unwrap();
+            if guard.0.elapsed() >= SAVE_DEBOUNCE {
+                guard.1 = false;
+                drop(guard);
+                save(&window, &mut normal.lock().unwrap());
+                break;
+            }
+        });
+    });
+}
@@ -0,0 +1,140 @@
+// Webview zoom, driven by the View menu, Ctrl+scroll and the frontend
+// settings UI. Levels are tracked per window label so zooming one window
+// doesn't clobber another's. The main window's level is persisted and is
+// also the starting level for any new window. The step and range come from
+// settings (`zoom_step`, `zoom_min`, `zoom_max`).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+pub const DEFAULT_ZOOM_MIN: f64 = 0.5;
+pub const DEFAULT_ZOOM_MAX: f64 = 3.0;
+pub const DEFAULT_ZOOM_STEP: f64 = 0.1;
+
+/// Ctrl+scroll sends the same steps as the menu. Trackpads report many small
+/// deltas, so they're accumulated up to a mouse wheel notch first.
+pub const INIT_SCRIPT: &str = r#"
+(() => {
+  let pending = 0;
+  window.addEventListener("wheel", (e) => {
+    if (!e.ctrlKey) return;
+    e.preventDefault();
+    pending += e.deltaY;
+    if (Math.abs(pending) < 50) return;
+    const steps = pending < 0 ? 1 : -1;
+    pending = 0;
+    window.__TAURI_INTERNALS__?.invoke("zoom_by_steps", { steps });
+  }, { passive: false });
+})();
+"#;
+
+#[derive(Default)]
+pub struct ZoomState(Mutex<HashMap<String, f64>>);
+
+struct Limits {
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+/// The configured range and step, with nonsense values replaced by the
+/// defaults.
+fn limits<R: Runtime>(app: &tauri::AppHandle<R>) -> Limits {
+    let settings = app.state::<SettingsStore>().get();
+    let valid = |v: f64| v.is_finite() && v > 0.0;
+    let min = if valid(settings.zoom_min) { settings.zoom_min } else { DEFAULT_ZOOM_MIN };
+    let max = if valid(settings.zoom_max) && settings.zoom_max >= min {
+        settings.zoom_max
+    } else {
+        DEFAULT_ZOOM_MAX.max(min)
+    };
+    let step = if valid(settings.zoom_step) { settings.zoom_step } else { DEFAULT_ZOOM_STEP };
+    Limits { min, max, step }
+}
+
+fn clamp<R: Runtime>(app: &tauri::AppHandle<R>, level: f64) -> f64 {
+    let limits = limits(app);
+    level.clamp(limits.min, limits.max)
+}
+
+fn persisted_default<R: Runtime>(app: &tauri::AppHandle<R>) -> f64 {
+    clamp(app, app.state::<SettingsStore>().get().zoom)
+}
+
+/// Apply the persisted zoom level to a newly created window. Call before
+/// it's shown.
+pub fn init<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle();
+    if app.try_state::<ZoomState>().is_none() {
+        app.manage(ZoomState::default());
+    }
+    let level = persisted_default(app);
+    app.state::<ZoomState>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), level);
+    let _ = window.set_zoom(level);
+}
+
+fn current<R: Runtime>(window: &WebviewWindow<R>) -> f64 {
+    let app = window.app_handle();
+    let level = app
+        .state::<ZoomState>()
+        .0
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .copied();
+    level.unwrap_or_else(|| persisted_default(app))
+}
+
+fn apply<R: Runtime>(window: &WebviewWindow<R>, level: f64) -> f64 {
+    let app = window.app_handle();
+    let level = clamp(app, level);
+    app.state::<ZoomState>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), level);
+    let _ = window.set_zoom(level);
+    if window.label() == "main" {
+        app.state::<SettingsStore>().update(app, |s| s.zoom = level);
+    }
+    level
+}
+
+/// Zoom in (positive) or out (negative) by `steps` of the configured step.
+pub fn step<R: Runtime>(window: &WebviewWindow<R>, steps: i32) {
+    let step = limits(window.app_handle()).step;
+    apply(window, current(window) + f64::from(steps) * step);
+}
+
+pub fn reset<R: Runtime>(window: &WebviewWindow<R>) {
+    apply(window, 1.0);
+}
+
+/// Zoom level of the calling window.
+#[tauri::command]
+pub fn get_zoom(window: WebviewWindow) -> f64 {
+    current(&window)
+}
+
+/// Set the calling window's zoom level, clamped to the configured range.
+/// Returns the applied level.
+#[tauri::command]
+pub fn set_zoom(window: WebviewWindow, level: f64) -> f64 {
+    apply(&window, level)
+}
+
+/// Step the calling window's zoom, as the menu does. Used by Ctrl+scroll.
+/// Returns the applied level.
+#[tauri::command]
+pub fn zoom_by_steps(window: WebviewWindow, steps: i32) -> f64 {
+    step(&window, steps);
+    current(&window)
+}
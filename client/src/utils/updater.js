@@ -4,27 +4,51 @@
  * Checks for updates on startup (if running in Tauri) and via
  * the Help > "Check for Updates" menu item.
  *
- * Requires:
- *   - tauri-plugin-updater configured with a valid pubkey + endpoint in tauri.conf.json
- *   - @tauri-apps/plugin-updater and @tauri-apps/plugin-process npm packages
+ * Goes through the Rust updater commands rather than the JS plugin API, so
+ * checks use the selected release channel (stable or beta).
  */
 
-let checkFn = null;
-let relaunchFn = null;
+let invokeFn = null;
+let listenFn = null;
 
 async function loadPlugins() {
-  if (checkFn) return true;
+  if (invokeFn) return true;
   try {
-    const updater = await import('@tauri-apps/plugin-updater');
-    const process = await import('@tauri-apps/plugin-process');
-    checkFn = updater.check;
-    relaunchFn = process.relaunch;
+    const core = await import('@tauri-apps/api/core');
+    const event = await import('@tauri-apps/api/event');
+    invokeFn = core.invoke;
+    listenFn = event.listen;
     return true;
   } catch {
     return false;
   }
 }
 
+/**
+ * Wrap an update found by `check_for_update`. `install` downloads it
+ * (reporting `{ downloaded, total }` to `onProgress`), installs it and
+ * restarts the app.
+ */
+function toUpdateInfo(update, onStatus) {
+  return {
+    version: update.version,
+    notes: update.notes,
+    install: async (onProgress) => {
+      onStatus?.(`Downloading v${update.version}...`);
+      const unlisten = await listenFn('update-progress', (event) => {
+        onProgress?.(event.payload);
+      });
+      try {
+        await invokeFn('download_and_stage_update');
+      } finally {
+        unlisten();
+      }
+      onStatus?.('Update installed! Restarting...');
+      await invokeFn('install_staged_update');
+    }
+  };
+}
+
 /**
  * Check for updates. Returns update info or null if up to date.
  * Shows UI feedback via the provided callbacks.
@@ -39,31 +63,18 @@ export async function checkForUpdates({ onStatus, onUpdateAvailable, onError } =
   onStatus?.('Checking for updates...');
 
   try {
-    const update = await checkFn();
+    const update = await invokeFn('check_for_update', { force: true });
 
     if (update) {
-      onUpdateAvailable?.({
-        version: update.version,
-        notes: update.body,
-        date: update.date,
-        install: async (onProgress) => {
-          onStatus?.(`Downloading v${update.version}...`);
-          await update.downloadAndInstall((event) => {
-            if (event.event === 'Progress' && onProgress) {
-              onProgress(event.data);
-            }
-          });
-          onStatus?.('Update installed! Restarting...');
-          await relaunchFn();
-        }
-      });
-      return update;
+      const info = toUpdateInfo(update, onStatus);
+      onUpdateAvailable?.(info);
+      return info;
     } else {
       onStatus?.('You are on the latest version.');
       return null;
     }
   } catch (err) {
-    onError?.(err?.message || 'Failed to check for updates');
+    onError?.(typeof err === 'string' ? err : err?.message || 'Failed to check for updates');
     return null;
   }
 }
@@ -90,21 +101,9 @@ export function autoCheckOnStartup(callbacks) {
     if (!loaded) return;
 
     try {
-      const update = await checkFn();
+      const update = await invokeFn('check_for_update');
       if (update) {
-        callbacks?.onUpdateAvailable?.({
-          version: update.version,
-          notes: update.body,
-          date: update.date,
-          install: async (onProgress) => {
-            await update.downloadAndInstall((event) => {
-              if (event.event === 'Progress' && onProgress) {
-                onProgress(event.data);
-              }
-            });
-            await relaunchFn();
-          }
-        });
+        callbacks?.onUpdateAvailable?.(toUpdateInfo(update));
       }
     } catch {
       // Silent failure on auto-check